@@ -0,0 +1,292 @@
+use std::{
+    fmt::{Debug, Display, Formatter},
+    io::{Read, Write},
+};
+
+use anyhow::Result;
+use chacha20poly1305::{
+    ChaCha20Poly1305,
+    aead::{
+        OsRng,
+        rand_core::RngCore,
+        stream::{DecryptorBE32, EncryptorBE32, Nonce, StreamBE32},
+    },
+};
+
+use crate::{Finish, RwBuilder};
+
+/// The key type for the chacha20poly1305 cipher
+pub type ChaCha20Poly1305Key = chacha20poly1305::Key;
+
+/// The nonce prefix written in front of the encrypted stream
+type NoncePrefix = Nonce<ChaCha20Poly1305, StreamBE32<ChaCha20Poly1305>>;
+
+/// The number of plaintext bytes in every segment except for the last one
+const SEGMENT_SIZE: usize = 64 * 1024;
+
+/// The number of bytes the authentication tag adds to every segment
+const TAG_SIZE: usize = 16;
+
+/// Type returned by the `aead_chacha20poly1305` function on the `RwBuilder`
+/// trait. It is itself an `RwBuilder` so can be chained further.
+#[derive(Debug)]
+pub struct Builder<B> {
+    /// The inner builder it wraps
+    builder: B,
+    /// The key used for encryption and decryption
+    key: ChaCha20Poly1305Key,
+}
+
+impl<B> Builder<B> {
+    /// Create a new authenticated cipher builder from a key
+    pub const fn new(builder: B, key: ChaCha20Poly1305Key) -> Self {
+        Self { builder, key }
+    }
+}
+
+impl<B> RwBuilder for Builder<B>
+where
+    B: RwBuilder,
+    B::Writer: Finish,
+{
+    type Reader = Reader<B::Reader>;
+    type Writer = Writer<B::Writer>;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        let mut reader = self.builder.reader()?;
+        let mut nonce = NoncePrefix::default();
+        reader.read_exact(&mut nonce).map_err(|e| match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => AeadError::Truncated.into(),
+            _ => e,
+        })?;
+        let decryptor = DecryptorBE32::new(&self.key, &nonce);
+        Ok(Reader { decryptor: Some(decryptor), reader, buffer: vec![], position: 0, failed: None })
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        let mut writer = self.builder.writer()?;
+        let mut nonce = NoncePrefix::default();
+        OsRng.fill_bytes(&mut nonce);
+        writer.write_all(&nonce)?;
+        let encryptor = EncryptorBE32::new(&self.key, &nonce);
+        Ok(Writer { encryptor: Some(encryptor), writer: Some(writer), buffer: vec![] })
+    }
+}
+
+/// The error reported by the reader when the authenticated stream can't be
+/// trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AeadError {
+    /// A segment failed authentication, because it was modified, reordered or
+    /// cut short
+    Tampered,
+    /// The stream ended right before a segment or in the nonce prefix
+    Truncated,
+}
+
+impl Display for AeadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tampered => write!(f, "the authenticated stream was tampered with"),
+            Self::Truncated => write!(f, "the authenticated stream was truncated"),
+        }
+    }
+}
+
+impl std::error::Error for AeadError {}
+
+impl From<AeadError> for std::io::Error {
+    fn from(error: AeadError) -> Self {
+        Self::new(std::io::ErrorKind::InvalidData, error)
+    }
+}
+
+/// Reader that decrypts and authenticates one segment at a time.
+/// Once a segment fails authentication or the stream turns out to be
+/// truncated, every following read fails with the same error.
+#[allow(clippy::struct_field_names)]
+pub struct Reader<R> {
+    /// The decryptor, which is consumed when the last segment is decrypted
+    decryptor: Option<DecryptorBE32<ChaCha20Poly1305>>,
+    /// The wrapped reader
+    reader: R,
+    /// The plaintext of the current segment
+    buffer: Vec<u8>,
+    /// The read position in the plaintext of the current segment
+    position: usize,
+    /// The error every read fails with once the stream couldn't be trusted
+    failed: Option<AeadError>,
+}
+
+impl<R> Debug for Reader<R>
+where
+    R: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Reader").field("reader", &self.reader).finish_non_exhaustive()
+    }
+}
+
+impl<R> Reader<R>
+where
+    R: Read,
+{
+    /// Discard the buffer, which can't be trusted, and make every following
+    /// read fail with `error`
+    fn fail(&mut self, error: AeadError) -> std::io::Error {
+        self.buffer.clear();
+        self.position = 0;
+        self.failed = Some(error);
+        error.into()
+    }
+
+    /// Read and decrypt the next segment into the buffer
+    fn read_segment(&mut self) -> std::io::Result<()> {
+        let Some(mut decryptor) = self.decryptor.take() else {
+            self.buffer.clear();
+            self.position = 0;
+            return Ok(());
+        };
+        self.buffer.resize(SEGMENT_SIZE + TAG_SIZE, 0);
+        let mut length = 0;
+        while length < self.buffer.len() {
+            match self.reader.read(&mut self.buffer[length..]) {
+                Ok(0) => break,
+                Ok(bytes) => length += bytes,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    // The bytes read so far are lost, so the segment can't
+                    // be completed anymore
+                    let _ = self.fail(AeadError::Truncated);
+                    return Err(e);
+                }
+            }
+        }
+        self.buffer.truncate(length);
+        self.position = 0;
+        if length == 0 {
+            return Err(self.fail(AeadError::Truncated));
+        }
+        if length == SEGMENT_SIZE + TAG_SIZE {
+            if decryptor.decrypt_next_in_place(&[], &mut self.buffer).is_err() {
+                return Err(self.fail(AeadError::Tampered));
+            }
+            self.decryptor = Some(decryptor);
+        } else if decryptor.decrypt_last_in_place(&[], &mut self.buffer).is_err() {
+            return Err(self.fail(AeadError::Tampered));
+        }
+        Ok(())
+    }
+}
+
+impl<R> Read for Reader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if let Some(error) = self.failed {
+            return Err(error.into());
+        }
+        if self.position == self.buffer.len() {
+            self.read_segment()?;
+        }
+        let byte_count = (self.buffer.len() - self.position).min(buf.len());
+        buf[..byte_count].copy_from_slice(&self.buffer[self.position..self.position + byte_count]);
+        self.position += byte_count;
+        Ok(byte_count)
+    }
+}
+
+/// Writer that encrypts and authenticates one segment at a time
+/// The last segment is only written when the writer is finished or dropped.
+#[allow(clippy::struct_field_names)]
+pub struct Writer<W>
+where
+    W: Write + Finish,
+{
+    /// The encryptor, which is consumed when the last segment is encrypted
+    encryptor: Option<EncryptorBE32<ChaCha20Poly1305>>,
+    /// The wrapped writer, which is taken when the writer is finished
+    writer: Option<W>,
+    /// The plaintext of the current segment
+    buffer: Vec<u8>,
+}
+
+impl<W> Debug for Writer<W>
+where
+    W: Write + Finish + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Writer").field("writer", &self.writer).finish_non_exhaustive()
+    }
+}
+
+impl<W> Writer<W>
+where
+    W: Write + Finish,
+{
+    /// Encrypt the buffer as the next segment and write it
+    fn write_segment(&mut self, last: bool) -> std::io::Result<()> {
+        let (Some(writer), Some(mut encryptor)) = (self.writer.as_mut(), self.encryptor.take())
+        else {
+            return Err(std::io::Error::other("the authenticated stream was already finished"));
+        };
+        if last {
+            encryptor.encrypt_last_in_place(&[], &mut self.buffer)
+        } else {
+            let result = encryptor.encrypt_next_in_place(&[], &mut self.buffer);
+            self.encryptor = Some(encryptor);
+            result
+        }
+        .map_err(|_| std::io::Error::other("the authenticated stream is too long"))?;
+        writer.write_all(&self.buffer)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl<W> Write for Writer<W>
+where
+    W: Write + Finish,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.buffer.len() == SEGMENT_SIZE {
+            self.write_segment(false)?;
+        }
+        let byte_count = (SEGMENT_SIZE - self.buffer.len()).min(buf.len());
+        self.buffer.extend_from_slice(&buf[..byte_count]);
+        Ok(byte_count)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.as_mut().map_or(Ok(()), Write::flush)
+    }
+}
+
+impl<W> Finish for Writer<W>
+where
+    W: Write + Finish,
+{
+    fn finish(mut self) -> Result<()> {
+        if self.buffer.len() == SEGMENT_SIZE {
+            self.write_segment(false)?;
+        }
+        self.write_segment(true)?;
+        self.writer.take().map_or(Ok(()), Finish::finish)
+    }
+}
+
+impl<W> Drop for Writer<W>
+where
+    W: Write + Finish,
+{
+    fn drop(&mut self) {
+        if self.encryptor.is_some() {
+            // Errors can't be reported while dropping, use `finish` instead
+            if self.buffer.len() == SEGMENT_SIZE {
+                let _ = self.write_segment(false);
+            }
+            let _ = self.write_segment(true);
+        }
+    }
+}
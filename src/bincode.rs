@@ -1,23 +1,21 @@
 use anyhow::Result;
 
-use crate::{RwBuilder, SerDe};
+#[cfg(feature = "async-tokio")]
+use crate::{AsyncRwBuilder, AsyncSerDe};
+use crate::{Finish, RwBuilder, SerDe};
 
 /// Type returned by the `bincode` function on the `RwBuilder` trait.
 /// It is itself not an `RwBuilder` so can't be chained further.
 /// This is why we call it a sink.
+///
+/// On top of an `AsyncRwBuilder` it is an `AsyncSerDe` as well.
 #[derive(Debug)]
-pub struct Builder<B>
-where
-    B: RwBuilder,
-{
+pub struct Builder<B> {
     /// The inner builder it wraps
     builder: B,
 }
 
-impl<B> Builder<B>
-where
-    B: RwBuilder,
-{
+impl<B> Builder<B> {
     /// Factory function to wrap an inner builder
     #[must_use]
     pub const fn new(builder: B) -> Self {
@@ -29,7 +27,7 @@ impl<B> SerDe for Builder<B>
 where
     B: RwBuilder,
     B::Reader: std::io::Read,
-    B::Writer: std::io::Write,
+    B::Writer: std::io::Write + Finish,
 {
     fn load<T>(&self) -> Result<T>
     where
@@ -43,7 +41,37 @@ where
     where
         T: serde::ser::Serialize,
     {
-        bincode::serialize_into(self.builder.writer()?, value)?;
-        Ok(())
+        let mut writer = self.builder.writer()?;
+        bincode::serialize_into(&mut writer, value)?;
+        writer.finish()
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+// The futures are `Send` if those of the inner builder are
+#[allow(clippy::future_not_send)]
+impl<B> AsyncSerDe for Builder<B>
+where
+    B: AsyncRwBuilder,
+{
+    async fn async_load<T>(&self) -> Result<T>
+    where
+        T: for<'de> serde::de::Deserialize<'de>,
+    {
+        use tokio::io::AsyncReadExt;
+        let mut buffer = vec![];
+        let _ = self.builder.async_reader().await?.read_to_end(&mut buffer).await?;
+        Ok(bincode::deserialize(&buffer)?)
+    }
+
+    async fn async_save<T>(&self, value: &T) -> Result<()>
+    where
+        T: serde::ser::Serialize,
+    {
+        use tokio::io::AsyncWriteExt;
+        let buffer = bincode::serialize(value)?;
+        let mut writer = self.builder.async_writer().await?;
+        writer.write_all(&buffer).await?;
+        Ok(writer.shutdown().await?)
     }
 }
@@ -0,0 +1,144 @@
+use std::{
+    fmt::{Debug, Formatter},
+    io::Write,
+};
+
+use anyhow::Result;
+
+use crate::{Codec, CoderBuilder, Constructor, Finish};
+
+/// The size of the internal buffers of the encoder and decoder
+const BUFFER_SIZE: usize = 4096;
+
+/// The Brotli encoder and decoder builder, whose level is the Brotli quality
+/// between 0 and 11
+#[derive(Debug, Copy, Clone)]
+pub struct Brotli {
+    /// The base two logarithm of the sliding window size, between 10 and 24
+    window: u32,
+}
+
+impl Brotli {
+    /// Create the encoder/decoder builder with a sliding window of
+    /// `2^window` bytes
+    #[must_use]
+    pub const fn with_window(window: u32) -> Self {
+        Self { window }
+    }
+}
+
+impl Default for Brotli {
+    /// The window the `brotli` command line tool uses by default
+    fn default() -> Self {
+        Self::with_window(22)
+    }
+}
+
+impl Codec for Brotli {
+    type Level = u32;
+}
+
+impl<B> Constructor<B> for Brotli {}
+
+impl<R, W> CoderBuilder<R, W> for Brotli
+where
+    R: std::io::Read,
+    W: Write,
+{
+    type Decoder = brotli::Decompressor<R>;
+    type Encoder = Encoder<W>;
+
+    fn encoder(&self, writer: W, quality: u32) -> std::io::Result<Self::Encoder> {
+        let output = Output { writer, error: None };
+        Ok(Encoder {
+            encoder: brotli::CompressorWriter::new(output, BUFFER_SIZE, quality, self.window),
+        })
+    }
+
+    fn decoder(&self, reader: R) -> std::io::Result<Self::Decoder> {
+        Ok(brotli::Decompressor::new(reader, BUFFER_SIZE))
+    }
+}
+
+/// Writer that compresses using Brotli and, like the flate2 encoders,
+/// finishes the stream when dropped
+pub struct Encoder<W>
+where
+    W: Write,
+{
+    /// The Brotli encoder writing to the inner writer
+    encoder: brotli::CompressorWriter<Output<W>>,
+}
+
+impl<W> Debug for Encoder<W>
+where
+    W: Write + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let writer = &self.encoder.get_ref().writer;
+        f.debug_struct("Encoder").field("writer", writer).finish_non_exhaustive()
+    }
+}
+
+impl<W> Write for Encoder<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.encoder.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.encoder.flush()
+    }
+}
+
+impl<W> Finish for Encoder<W>
+where
+    W: Write + Finish,
+{
+    fn finish(self) -> Result<()> {
+        // The Brotli encoder ignores errors while writing the end of the
+        // stream, so they are taken from the inner writer instead
+        let output = self.encoder.into_inner();
+        match output.error {
+            Some(error) => Err(error.into()),
+            None => output.writer.finish(),
+        }
+    }
+}
+
+/// The inner writer of the Brotli encoder, which remembers the last error
+/// that occurred while writing to it
+#[allow(clippy::struct_field_names)]
+struct Output<W> {
+    /// The wrapped writer
+    writer: W,
+    /// The last error returned by the wrapped writer
+    error: Option<std::io::Error>,
+}
+
+impl<W> Output<W>
+where
+    W: Write,
+{
+    /// Remember an error before passing a copy of it on
+    fn record(&mut self, error: std::io::Error) -> std::io::Error {
+        let copy = std::io::Error::new(error.kind(), error.to_string());
+        self.error = Some(error);
+        copy
+    }
+}
+
+impl<W> Write for Output<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write(buf).map_err(|error| self.record(error))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush().map_err(|error| self.record(error))
+    }
+}
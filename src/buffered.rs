@@ -1,24 +1,21 @@
-use std::io::{BufReader, BufWriter};
+use std::io::{BufReader, BufWriter, Seek};
 
 use anyhow::Result;
 
-use crate::RwBuilder;
+#[cfg(feature = "async-tokio")]
+use crate::AsyncRwBuilder;
+use crate::{Finish, RwBuilder, SeekableRwBuilder};
 
 /// Type returned by the `buffered` function on the `RwBuilder` trait.
-/// It is itself an `RwBuilder` so can be chained further.
+/// It is itself an `RwBuilder` so can be chained further, and an
+/// `AsyncRwBuilder` on top of one.
 #[derive(Debug)]
-pub struct Builder<B>
-where
-    B: RwBuilder,
-{
+pub struct Builder<B> {
     /// The inner builder it wraps
     builder: B,
 }
 
-impl<B> Builder<B>
-where
-    B: RwBuilder,
-{
+impl<B> Builder<B> {
     /// Factory function to wrap an inner builder
     #[must_use]
     pub const fn new(builder: B) -> Self {
@@ -30,7 +27,7 @@ impl<B> RwBuilder for Builder<B>
 where
     B: RwBuilder,
     B::Reader: std::io::Read,
-    B::Writer: std::io::Write,
+    B::Writer: std::io::Write + Finish,
 {
     type Reader = BufReader<B::Reader>;
     type Writer = BufWriter<B::Writer>;
@@ -43,3 +40,39 @@ where
         Ok(BufWriter::new(self.builder.writer()?))
     }
 }
+
+impl<B> SeekableRwBuilder for Builder<B>
+where
+    B: SeekableRwBuilder,
+    B::Reader: std::io::Read + Seek,
+    B::Writer: std::io::Write + Finish + Seek,
+{
+}
+
+impl<W> Finish for BufWriter<W>
+where
+    W: std::io::Write + Finish,
+{
+    fn finish(self) -> Result<()> {
+        self.into_inner().map_err(std::io::IntoInnerError::into_error)?.finish()
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+// The futures are `Send` if those of the inner builder are
+#[allow(clippy::future_not_send)]
+impl<B> AsyncRwBuilder for Builder<B>
+where
+    B: AsyncRwBuilder,
+{
+    type AsyncReader = tokio::io::BufReader<B::AsyncReader>;
+    type AsyncWriter = tokio::io::BufWriter<B::AsyncWriter>;
+
+    async fn async_reader(&self) -> Result<Self::AsyncReader> {
+        Ok(tokio::io::BufReader::new(self.builder.async_reader().await?))
+    }
+
+    async fn async_writer(&self) -> Result<Self::AsyncWriter> {
+        Ok(tokio::io::BufWriter::new(self.builder.async_writer().await?))
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+
+use crate::{Codec, CoderBuilder, Constructor, Finish};
+
+/// The bzip2 encoder and decoder builder, whose level is the block size
+/// between 1 and 9 like the options of the `bzip2` command line tool
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Bzip2;
+
+impl Codec for Bzip2 {
+    type Level = u32;
+}
+
+impl<R, W> CoderBuilder<R, W> for Bzip2
+where
+    R: std::io::Read,
+    W: std::io::Write,
+{
+    type Decoder = bzip2::read::MultiBzDecoder<R>;
+    type Encoder = bzip2::write::BzEncoder<W>;
+
+    /// Fails with `ErrorKind::InvalidInput` if the level isn't between 1 and 9
+    fn encoder(&self, writer: W, level: u32) -> std::io::Result<Self::Encoder> {
+        let compression = bzip2::Compression::try_new(level).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("the bzip2 level {level} isn't between 1 and 9"),
+            )
+        })?;
+        Ok(bzip2::write::BzEncoder::new(writer, compression))
+    }
+
+    /// The decoder reads all concatenated streams, like the `bzip2` command
+    /// line tool does
+    fn decoder(&self, reader: R) -> std::io::Result<Self::Decoder> {
+        Ok(bzip2::read::MultiBzDecoder::new(reader))
+    }
+}
+
+impl<B> Constructor<B> for Bzip2 {}
+
+impl<W> Finish for bzip2::write::BzEncoder<W>
+where
+    W: std::io::Write + Finish,
+{
+    fn finish(self) -> Result<()> {
+        Self::finish(self)?.finish()
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::Result;
+
+#[cfg(feature = "async-tokio")]
+use crate::AsyncRwBuilder;
+use crate::{Finish, RwBuilder};
+
+/// Type returned by the compression functions like `gz`, `xz` and `zstd` on
+/// the `RwBuilder` trait. It is itself an `RwBuilder` so can be chained
+/// further.
+///
+/// For the flate2 algorithms it is an `AsyncRwBuilder` on top of one as well.
+#[derive(Debug)]
+pub struct CompressionBuilder<B, C>
+where
+    C: Codec,
+{
+    /// The inner builder it wraps
+    builder: B,
+    /// The compression level used for the encoder
+    level: C::Level,
+    /// The builder for the encoder and decoder
+    coder: C,
+}
+
+impl<B, C> CompressionBuilder<B, C>
+where
+    C: Codec,
+{
+    /// Factory function to wrap an inner builder using an encoder/decoder
+    /// builder which carries its own configuration
+    #[must_use]
+    pub const fn with_coder(builder: B, level: C::Level, coder: C) -> Self {
+        Self { builder, level, coder }
+    }
+
+    /// Change the configuration of the encoder/decoder builder
+    #[must_use]
+    pub fn map_coder<F>(self, f: F) -> Self
+    where
+        F: FnOnce(C) -> C,
+    {
+        Self { coder: f(self.coder), ..self }
+    }
+}
+
+impl<B, C> RwBuilder for CompressionBuilder<B, C>
+where
+    B: RwBuilder,
+    B::Reader: std::io::Read,
+    B::Writer: std::io::Write + Finish,
+    C: CoderBuilder<B::Reader, B::Writer>,
+    C::Decoder: std::io::Read,
+    C::Encoder: std::io::Write + Finish,
+{
+    type Reader = C::Decoder;
+    type Writer = C::Encoder;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        let reader = self.builder.reader()?;
+        Ok(self.coder.decoder(reader)?)
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        let writer = self.builder.writer()?;
+        Ok(self.coder.encoder(writer, self.level)?)
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+// The futures are `Send` if those of the inner builder are
+#[allow(clippy::future_not_send)]
+impl<B, C> AsyncRwBuilder for CompressionBuilder<B, C>
+where
+    B: AsyncRwBuilder,
+    C: AsyncCoderBuilder<B::AsyncReader, B::AsyncWriter>,
+    C::Decoder: tokio::io::AsyncRead + Unpin,
+    C::Encoder: tokio::io::AsyncWrite + Unpin,
+{
+    type AsyncReader = C::Decoder;
+    type AsyncWriter = C::Encoder;
+
+    async fn async_reader(&self) -> Result<Self::AsyncReader> {
+        let reader = self.builder.async_reader().await?;
+        Ok(AsyncCoderBuilder::<B::AsyncReader, B::AsyncWriter>::decoder(&self.coder, reader))
+    }
+
+    async fn async_writer(&self) -> Result<Self::AsyncWriter> {
+        let writer = self.builder.async_writer().await?;
+        Ok(AsyncCoderBuilder::<B::AsyncReader, B::AsyncWriter>::encoder(
+            &self.coder,
+            writer,
+            self.level,
+        ))
+    }
+}
+
+/// Implementors like `Gz`, `Xz` and `Bzip2` define the type of the
+/// compression level their encoders accept, e.g. `Compression` for the flate2
+/// algorithms and a preset for xz.
+pub trait Codec {
+    /// The type of the compression level
+    type Level: Copy;
+}
+
+/// Implementors like `Deflate`, `Gz` and `Zlib` create the associated encoders
+/// and decoders.
+pub trait CoderBuilder<R, W>: Codec {
+    /// The type of encoder created
+    type Encoder;
+
+    /// Create an encoder on top of a writer
+    /// # Errors
+    /// Fails if the encoder doesn't support the level, or can't be set up
+    fn encoder(&self, writer: W, level: Self::Level) -> std::io::Result<Self::Encoder>;
+
+    /// The type of decoder created
+    type Decoder;
+
+    /// Create a decoder on top of a reader
+    /// # Errors
+    /// Fails if the decoder can't be set up
+    fn decoder(&self, reader: R) -> std::io::Result<Self::Decoder>;
+}
+
+/// The async counterpart of the `CoderBuilder` trait which creates tokio
+/// encoders and decoders.
+#[cfg(feature = "async-tokio")]
+pub trait AsyncCoderBuilder<R, W>: Codec {
+    /// The type of encoder created
+    type Encoder;
+
+    /// Create an encoder on top of a writer
+    fn encoder(&self, writer: W, level: Self::Level) -> Self::Encoder;
+
+    /// The type of decoder created
+    type Decoder;
+
+    /// Create a decoder on top of a reader
+    fn decoder(&self, reader: R) -> Self::Decoder;
+}
+
+/// Convenience trait for creating a new encoder/decoder builder
+pub trait Constructor<B>
+where
+    Self: Codec + Sized + Default,
+{
+    /// Create the encoder/decoder builder
+    fn new(builder: B, level: Self::Level) -> CompressionBuilder<B, Self> {
+        CompressionBuilder { builder, level, coder: Self::default() }
+    }
+}
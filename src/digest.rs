@@ -0,0 +1,151 @@
+use std::io::{Read, Write};
+
+use anyhow::Result;
+use digest::{Digest, Output};
+
+use crate::{Finish, RwBuilder};
+
+/// Type returned by the `digest` function on the `RwBuilder` trait.
+/// It is itself an `RwBuilder` so can be chained further.
+#[derive(Debug)]
+pub struct Builder<B, D> {
+    /// The inner builder it wraps
+    builder: B,
+    /// The hash algorithm used by the readers and writers
+    _marker: std::marker::PhantomData<D>,
+}
+
+impl<B, D> Builder<B, D> {
+    /// Factory function to wrap an inner builder
+    #[must_use]
+    pub const fn new(builder: B) -> Self {
+        Self { builder, _marker: std::marker::PhantomData }
+    }
+}
+
+impl<B, D> RwBuilder for Builder<B, D>
+where
+    B: RwBuilder,
+    D: Digest + Clone,
+{
+    type Reader = Reader<B::Reader, D>;
+    type Writer = Writer<B::Writer, D>;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        Ok(Reader { reader: self.builder.reader()?, hash: Hash::default() })
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        Ok(Writer { writer: self.builder.writer()?, hash: Hash::default() })
+    }
+}
+
+/// The running hash of the bytes that passed through a reader or writer
+#[derive(Debug, Clone)]
+pub struct Hash<D> {
+    /// The hasher fed with every byte
+    hasher: D,
+    /// The number of bytes hashed
+    amount: u64,
+}
+
+impl<D> Default for Hash<D>
+where
+    D: Digest,
+{
+    fn default() -> Self {
+        Self { hasher: D::new(), amount: 0 }
+    }
+}
+
+impl<D> Hash<D>
+where
+    D: Digest + Clone,
+{
+    /// The hash of the bytes so far
+    #[must_use]
+    pub fn sum(&self) -> Output<D> {
+        self.hasher.clone().finalize()
+    }
+
+    /// The number of bytes hashed so far
+    #[must_use]
+    pub const fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    /// Hash the bytes
+    fn update(&mut self, bytes: &[u8]) {
+        self.hasher.update(bytes);
+        self.amount += bytes.len() as u64;
+    }
+}
+
+/// Reader that hashes the bytes read from the inner reader
+#[derive(Debug)]
+pub struct Reader<R, D> {
+    /// The wrapped reader
+    reader: R,
+    /// The running hash
+    hash: Hash<D>,
+}
+
+impl<R, D> Reader<R, D> {
+    /// The running hash of the bytes read so far
+    pub const fn digest(&self) -> &Hash<D> {
+        &self.hash
+    }
+}
+
+impl<R, D> Read for Reader<R, D>
+where
+    R: Read,
+    D: Digest + Clone,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let byte_count = self.reader.read(buf)?;
+        self.hash.update(&buf[..byte_count]);
+        Ok(byte_count)
+    }
+}
+
+/// Writer that hashes the bytes written to the inner writer
+#[derive(Debug)]
+pub struct Writer<W, D> {
+    /// The wrapped writer
+    writer: W,
+    /// The running hash
+    hash: Hash<D>,
+}
+
+impl<W, D> Writer<W, D> {
+    /// The running hash of the bytes written so far
+    pub const fn digest(&self) -> &Hash<D> {
+        &self.hash
+    }
+}
+
+impl<W, D> Write for Writer<W, D>
+where
+    W: Write,
+    D: Digest + Clone,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let byte_count = self.writer.write(buf)?;
+        self.hash.update(&buf[..byte_count]);
+        Ok(byte_count)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<W, D> Finish for Writer<W, D>
+where
+    W: Finish,
+{
+    fn finish(self) -> Result<()> {
+        self.writer.finish()
+    }
+}
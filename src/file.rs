@@ -1,8 +1,27 @@
-use std::{fs::OpenOptions, path::PathBuf};
+use std::{
+    fs::OpenOptions,
+    io::{Seek, SeekFrom, Write},
+    path::PathBuf,
+};
 
 use anyhow::Result;
 
-use crate::RwBuilder;
+#[cfg(feature = "async-tokio")]
+use crate::AsyncRwBuilder;
+use crate::{Finish, RwBuilder, SeekableRwBuilder, temp_path};
+
+/// How the writer treats the contents of an existing file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WriteMode {
+    /// Discard the existing contents
+    Truncate,
+    /// Keep the existing contents and write after them
+    Append,
+    /// Keep the existing contents and write over them from the start
+    Overwrite,
+    /// Fail if the file already exists
+    CreateNew,
+}
 
 /// Type for building readers and writers on top of a file handle.
 /// It is itself an `RwBuilder`, but can't be created through one.
@@ -11,13 +30,78 @@ use crate::RwBuilder;
 pub struct Builder {
     /// The path of the file for which readers and writers can be created.
     path: PathBuf,
+    /// How the writer treats the contents of an existing file
+    write_mode: WriteMode,
+    /// The permission bits of a file created by the writer
+    #[cfg(unix)]
+    mode: Option<u32>,
 }
 
 impl Builder {
     /// Factory function to create a builder holding on to a file path
     #[must_use]
     pub const fn new(path: PathBuf) -> Self {
-        Self { path }
+        Self {
+            path,
+            write_mode: WriteMode::Truncate,
+            #[cfg(unix)]
+            mode: None,
+        }
+    }
+
+    /// Make the writer append to the end of an existing file instead of
+    /// truncating it
+    #[must_use]
+    pub fn append(self) -> Self {
+        Self { write_mode: WriteMode::Append, ..self }
+    }
+
+    /// Make the writer write over the existing contents from the start instead
+    /// of truncating the file, e.g. to patch it through `writer_at`
+    #[must_use]
+    pub fn overwrite(self) -> Self {
+        Self { write_mode: WriteMode::Overwrite, ..self }
+    }
+
+    /// Make the writer fail with `ErrorKind::AlreadyExists` if the file
+    /// already exists
+    #[must_use]
+    pub fn create_new(self) -> Self {
+        Self { write_mode: WriteMode::CreateNew, ..self }
+    }
+
+    /// Set the permission bits, e.g. `0o600`, of a file created by the writer.
+    /// The process umask still applies and existing files keep theirs.
+    #[cfg(unix)]
+    #[must_use]
+    pub fn mode(self, mode: u32) -> Self {
+        Self { mode: Some(mode), ..self }
+    }
+
+    /// Make the writer write to a temporary file next to the target, which
+    /// only replaces the target once the writer is finished successfully.
+    /// The temporary file starts out as a copy of the target when appending
+    /// or overwriting, and with `create_new` finishing the writer fails if
+    /// the target was created in the meantime.
+    #[must_use]
+    pub const fn atomic(self) -> AtomicBuilder {
+        AtomicBuilder { builder: self }
+    }
+
+    /// The options used to open the file for writing
+    fn write_options(&self) -> OpenOptions {
+        let mut options = OpenOptions::new();
+        let _ = match self.write_mode {
+            WriteMode::Truncate => options.create(true).write(true).truncate(true),
+            WriteMode::Append => options.create(true).append(true),
+            WriteMode::Overwrite => options.create(true).write(true).truncate(false),
+            WriteMode::CreateNew => options.create_new(true).write(true),
+        };
+        #[cfg(unix)]
+        if let Some(mode) = self.mode {
+            let _ = std::os::unix::fs::OpenOptionsExt::mode(&mut options, mode);
+        }
+        options
     }
 }
 
@@ -31,7 +115,166 @@ impl RwBuilder for Builder {
     }
 
     fn writer(&self) -> Result<Self::Writer> {
-        let options = OpenOptions::new().create(true).write(true).open(&self.path)?;
+        let options = self.write_options().open(&self.path)?;
         Ok(options)
     }
 }
+
+impl SeekableRwBuilder for Builder {}
+
+/// Type returned by the `atomic` function on the file `Builder`. Readers read
+/// the target file, writers only replace it when they are finished.
+/// It is itself an `RwBuilder`, so it can be chained further.
+#[derive(Debug)]
+pub struct AtomicBuilder {
+    /// The builder of the target file
+    builder: Builder,
+}
+
+impl RwBuilder for AtomicBuilder {
+    type Reader = std::fs::File;
+    type Writer = AtomicWriter;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        RwBuilder::reader(&self.builder)
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        let path = &self.builder.path;
+        let write_mode = self.builder.write_mode;
+        let target = match std::fs::metadata(path) {
+            Ok(metadata) => Some(metadata),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        if write_mode == WriteMode::CreateNew && target.is_some() {
+            return Err(std::io::Error::from(std::io::ErrorKind::AlreadyExists).into());
+        }
+        let temp_path = temp_path(path);
+        let mut options = OpenOptions::new();
+        let _ = options.create_new(true).write(true);
+        let _ = options.append(write_mode == WriteMode::Append);
+        #[cfg(unix)]
+        if let Some(mode) = self.builder.mode {
+            let _ = std::os::unix::fs::OpenOptionsExt::mode(&mut options, mode);
+        }
+        let file = options.open(&temp_path)?;
+        let mut writer = AtomicWriter {
+            file: Some(file),
+            temp_path,
+            path: path.clone(),
+            no_clobber: write_mode == WriteMode::CreateNew,
+        };
+        if let Some(target) = target {
+            // The replaced file keeps its permissions, like it does when it is
+            // written in place
+            std::fs::set_permissions(&writer.temp_path, target.permissions())?;
+            if matches!(write_mode, WriteMode::Append | WriteMode::Overwrite) {
+                let file = writer.file()?;
+                let _ = std::io::copy(&mut std::fs::File::open(path)?, file)?;
+                let _ = file.seek(SeekFrom::Start(0))?;
+            }
+        }
+        Ok(writer)
+    }
+}
+
+impl SeekableRwBuilder for AtomicBuilder {}
+
+/// Writer to a temporary file which is renamed over the target file when the
+/// writer is finished, and removed when the writer is dropped without
+/// finishing it.
+#[derive(Debug)]
+pub struct AtomicWriter {
+    /// The temporary file, which is taken when the writer is finished
+    file: Option<std::fs::File>,
+    /// The path of the temporary file
+    temp_path: PathBuf,
+    /// The path of the target file
+    path: PathBuf,
+    /// Whether the target file may not be replaced if it exists
+    no_clobber: bool,
+}
+
+impl AtomicWriter {
+    /// Sync the temporary file to disk and rename it over the target file
+    fn commit(&mut self) -> Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+            file.sync_all()?;
+            drop(file);
+            if self.no_clobber {
+                // Unlike renaming, linking fails if the target exists, the
+                // temporary file is removed when the writer is dropped
+                std::fs::hard_link(&self.temp_path, &self.path)?;
+            } else {
+                std::fs::rename(&self.temp_path, &self.path)?;
+            }
+            #[cfg(unix)]
+            if let Some(parent) = self.path.parent() {
+                let parent = if parent.as_os_str().is_empty() { ".".as_ref() } else { parent };
+                std::fs::File::open(parent)?.sync_all()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The temporary file, or an error once the writer is finished
+    fn file(&mut self) -> std::io::Result<&mut std::fs::File> {
+        self.file.as_mut().ok_or_else(|| std::io::Error::other("the file was already renamed"))
+    }
+}
+
+impl Write for AtomicWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file()?.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file()?.flush()
+    }
+}
+
+impl Seek for AtomicWriter {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.file()?.seek(pos)
+    }
+}
+
+impl Finish for AtomicWriter {
+    fn finish(mut self) -> Result<()> {
+        self.commit()
+    }
+}
+
+impl Drop for AtomicWriter {
+    fn drop(&mut self) {
+        // Fails once the temporary file is renamed, errors can't be reported
+        // while dropping anyway
+        drop(self.file.take());
+        let _ = std::fs::remove_file(&self.temp_path);
+    }
+}
+
+impl Finish for std::fs::File {
+    fn finish(mut self) -> Result<()> {
+        Ok(self.flush()?)
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl AsyncRwBuilder for Builder {
+    type AsyncReader = tokio::fs::File;
+    type AsyncWriter = tokio::fs::File;
+
+    async fn async_reader(&self) -> Result<Self::AsyncReader> {
+        let file = tokio::fs::OpenOptions::new().read(true).open(&self.path).await?;
+        Ok(file)
+    }
+
+    async fn async_writer(&self) -> Result<Self::AsyncWriter> {
+        let options = tokio::fs::OpenOptions::from(self.write_options());
+        let file = options.open(&self.path).await?;
+        Ok(file)
+    }
+}
@@ -1,102 +1,80 @@
 use anyhow::Result;
 use flate2::{Compression, CrcReader, CrcWriter};
 
-use crate::RwBuilder;
+#[cfg(feature = "async-tokio")]
+use crate::AsyncCoderBuilder;
+use crate::{Codec, CoderBuilder, Constructor, Finish, RwBuilder};
 
-/// Type returned by the `deflate`, `gz` and `zlib` functions on the `RwBuilder`
-/// trait. It is itself an `RwBuilder` so can be chained further.
-#[derive(Debug)]
-pub struct CompressionBuilder<B, C>
-where
-    B: RwBuilder,
-    C: CoderBuilder<B::Reader, B::Writer>,
-{
-    /// The inner builder it wraps
-    builder: B,
-    /// The compression used for the encoder
-    compression: Compression,
-    /// The builder for the encoder and decoder
-    coder: C,
+/// The Zlib encoder and decoder builder
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Zlib;
+
+impl Codec for Zlib {
+    type Level = Compression;
 }
 
-impl<B, C> RwBuilder for CompressionBuilder<B, C>
+impl<R, W> CoderBuilder<R, W> for Zlib
 where
-    B: RwBuilder,
-    B::Reader: std::io::Read,
-    B::Writer: std::io::Write,
-    C: CoderBuilder<B::Reader, B::Writer>,
-    C::Decoder: std::io::Read,
-    C::Encoder: std::io::Write,
+    R: std::io::Read,
+    W: std::io::Write,
 {
-    type Reader = C::Decoder;
-    type Writer = C::Encoder;
+    type Decoder = flate2::read::ZlibDecoder<R>;
+    type Encoder = flate2::write::ZlibEncoder<W>;
 
-    fn reader(&self) -> Result<Self::Reader> {
-        let reader = self.builder.reader()?;
-        Ok(self.coder.decoder(reader))
+    fn encoder(&self, writer: W, compression: Compression) -> std::io::Result<Self::Encoder> {
+        Ok(flate2::write::ZlibEncoder::new(writer, compression))
     }
 
-    fn writer(&self) -> Result<Self::Writer> {
-        let writer = self.builder.writer()?;
-        Ok(self.coder.encoder(writer, self.compression))
+    fn decoder(&self, reader: R) -> std::io::Result<Self::Decoder> {
+        Ok(flate2::read::ZlibDecoder::new(reader))
     }
 }
 
-/// Implementors like `Deflate`, `Gz` and `Zlib` create the associated encoders
-/// and decoders.
-pub trait CoderBuilder<R, W> {
-    /// The type of encoder created
-    type Encoder;
-
-    /// Create an encoder on top of a writer
-    fn encoder(&self, writer: W, compression: Compression) -> Self::Encoder;
+/// Translate the flate2 compression level into the one used by
+/// `async-compression`
+#[cfg(feature = "async-tokio")]
+fn async_level(compression: Compression) -> async_compression::Level {
+    i32::try_from(compression.level())
+        .map_or(async_compression::Level::Default, async_compression::Level::Precise)
+}
 
-    /// The type of decoder created
-    type Decoder;
+impl<B> Constructor<B> for Zlib {}
 
-    /// Create a decoder on top of a reader
-    fn decoder(&self, reader: R) -> Self::Decoder;
+impl<W> Finish for flate2::write::ZlibEncoder<W>
+where
+    W: std::io::Write + Finish,
+{
+    fn finish(self) -> Result<()> {
+        Self::finish(self)?.finish()
+    }
 }
 
-/// The Zlib encoder and decoder builder
-#[derive(Default, Debug, Copy, Clone)]
-pub struct Zlib;
-
-impl<R, W> CoderBuilder<R, W> for Zlib
+#[cfg(feature = "async-tokio")]
+impl<R, W> AsyncCoderBuilder<R, W> for Zlib
 where
-    R: std::io::Read,
-    W: std::io::Write,
+    R: tokio::io::AsyncRead,
+    W: tokio::io::AsyncWrite,
 {
-    type Decoder = flate2::read::ZlibDecoder<R>;
-    type Encoder = flate2::write::ZlibEncoder<W>;
+    type Decoder = async_compression::tokio::bufread::ZlibDecoder<tokio::io::BufReader<R>>;
+    type Encoder = async_compression::tokio::write::ZlibEncoder<W>;
 
     fn encoder(&self, writer: W, compression: Compression) -> Self::Encoder {
-        flate2::write::ZlibEncoder::new(writer, compression)
+        async_compression::tokio::write::ZlibEncoder::with_quality(writer, async_level(compression))
     }
 
     fn decoder(&self, reader: R) -> Self::Decoder {
-        flate2::read::ZlibDecoder::new(reader)
-    }
-}
-
-/// Convenience trait for creating a new encoder/decoder builder
-pub trait Constructor<B>
-where
-    Self: Sized + CoderBuilder<B::Reader, B::Writer> + Default,
-    B: RwBuilder,
-{
-    /// Create the encoder/decoder builder
-    fn new(builder: B, compression: Compression) -> CompressionBuilder<B, Self> {
-        CompressionBuilder { builder, compression, coder: Self::default() }
+        async_compression::tokio::bufread::ZlibDecoder::new(tokio::io::BufReader::new(reader))
     }
 }
 
-impl<B> Constructor<B> for Zlib where B: RwBuilder {}
-
 /// The Gz encoder and decoder builder
 #[derive(Default, Debug, Copy, Clone)]
 pub struct Gz;
 
+impl Codec for Gz {
+    type Level = Compression;
+}
+
 impl<R, W> CoderBuilder<R, W> for Gz
 where
     R: std::io::Read,
@@ -105,21 +83,52 @@ where
     type Decoder = flate2::read::GzDecoder<R>;
     type Encoder = flate2::write::GzEncoder<W>;
 
+    fn encoder(&self, writer: W, compression: Compression) -> std::io::Result<Self::Encoder> {
+        Ok(flate2::write::GzEncoder::new(writer, compression))
+    }
+
+    fn decoder(&self, reader: R) -> std::io::Result<Self::Decoder> {
+        Ok(flate2::read::GzDecoder::new(reader))
+    }
+}
+
+impl<B> Constructor<B> for Gz {}
+
+impl<W> Finish for flate2::write::GzEncoder<W>
+where
+    W: std::io::Write + Finish,
+{
+    fn finish(self) -> Result<()> {
+        Self::finish(self)?.finish()
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<R, W> AsyncCoderBuilder<R, W> for Gz
+where
+    R: tokio::io::AsyncRead,
+    W: tokio::io::AsyncWrite,
+{
+    type Decoder = async_compression::tokio::bufread::GzipDecoder<tokio::io::BufReader<R>>;
+    type Encoder = async_compression::tokio::write::GzipEncoder<W>;
+
     fn encoder(&self, writer: W, compression: Compression) -> Self::Encoder {
-        flate2::write::GzEncoder::new(writer, compression)
+        async_compression::tokio::write::GzipEncoder::with_quality(writer, async_level(compression))
     }
 
     fn decoder(&self, reader: R) -> Self::Decoder {
-        flate2::read::GzDecoder::new(reader)
+        async_compression::tokio::bufread::GzipDecoder::new(tokio::io::BufReader::new(reader))
     }
 }
 
-impl<B> Constructor<B> for Gz where B: RwBuilder {}
-
 /// The Deflate encoder and decoder builder
 #[derive(Default, Debug, Copy, Clone)]
 pub struct Deflate;
 
+impl Codec for Deflate {
+    type Level = Compression;
+}
+
 impl<R, W> CoderBuilder<R, W> for Deflate
 where
     R: std::io::Read,
@@ -128,17 +137,47 @@ where
     type Decoder = flate2::read::DeflateDecoder<R>;
     type Encoder = flate2::write::DeflateEncoder<W>;
 
+    fn encoder(&self, writer: W, compression: Compression) -> std::io::Result<Self::Encoder> {
+        Ok(flate2::write::DeflateEncoder::new(writer, compression))
+    }
+
+    fn decoder(&self, reader: R) -> std::io::Result<Self::Decoder> {
+        Ok(flate2::read::DeflateDecoder::new(reader))
+    }
+}
+
+impl<B> Constructor<B> for Deflate {}
+
+impl<W> Finish for flate2::write::DeflateEncoder<W>
+where
+    W: std::io::Write + Finish,
+{
+    fn finish(self) -> Result<()> {
+        Self::finish(self)?.finish()
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<R, W> AsyncCoderBuilder<R, W> for Deflate
+where
+    R: tokio::io::AsyncRead,
+    W: tokio::io::AsyncWrite,
+{
+    type Decoder = async_compression::tokio::bufread::DeflateDecoder<tokio::io::BufReader<R>>;
+    type Encoder = async_compression::tokio::write::DeflateEncoder<W>;
+
     fn encoder(&self, writer: W, compression: Compression) -> Self::Encoder {
-        flate2::write::DeflateEncoder::new(writer, compression)
+        async_compression::tokio::write::DeflateEncoder::with_quality(
+            writer,
+            async_level(compression),
+        )
     }
 
     fn decoder(&self, reader: R) -> Self::Decoder {
-        flate2::read::DeflateDecoder::new(reader)
+        async_compression::tokio::bufread::DeflateDecoder::new(tokio::io::BufReader::new(reader))
     }
 }
 
-impl<B> Constructor<B> for Deflate where B: RwBuilder {}
-
 /// Type returned by the `crc` function on the `RwBuilder` trait.
 /// It is itself an `RwBuilder` so can be chained further, although this is an
 /// uncommon scenario
@@ -166,7 +205,7 @@ impl<B> RwBuilder for CrcBuilder<B>
 where
     B: RwBuilder,
     B::Reader: std::io::Read,
-    B::Writer: std::io::Write,
+    B::Writer: std::io::Write + Finish,
 {
     type Reader = CrcReader<B::Reader>;
     type Writer = CrcWriter<B::Writer>;
@@ -179,3 +218,12 @@ where
         Ok(CrcWriter::new(self.builder.writer()?))
     }
 }
+
+impl<W> Finish for CrcWriter<W>
+where
+    W: std::io::Write + Finish,
+{
+    fn finish(self) -> Result<()> {
+        self.into_inner().finish()
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+
+#[cfg(feature = "async-tokio")]
+use crate::{AsyncRwBuilder, AsyncSerDe};
+use crate::{Finish, RwBuilder, SerDe};
+
+/// Type returned by the `json` function on the `RwBuilder` trait.
+/// It is itself not an `RwBuilder` so can't be chained further.
+/// This is why we call it a sink.
+///
+/// On top of an `AsyncRwBuilder` it is an `AsyncSerDe` as well.
+#[derive(Debug)]
+pub struct Builder<B> {
+    /// The inner builder it wraps
+    builder: B,
+    /// Whether the JSON is written with indentation and newlines
+    pretty: bool,
+}
+
+impl<B> Builder<B> {
+    /// Factory function to wrap an inner builder
+    #[must_use]
+    pub const fn new(builder: B) -> Self {
+        Self { builder, pretty: false }
+    }
+
+    /// Write human friendly JSON with indentation and newlines
+    #[must_use]
+    pub fn pretty(self) -> Self {
+        Self { builder: self.builder, pretty: true }
+    }
+}
+
+impl<B> SerDe for Builder<B>
+where
+    B: RwBuilder,
+    B::Reader: std::io::Read,
+    B::Writer: std::io::Write + Finish,
+{
+    fn load<T>(&self) -> Result<T>
+    where
+        T: for<'de> serde::de::Deserialize<'de>,
+    {
+        let result = serde_json::from_reader(self.builder.reader()?)?;
+        Ok(result)
+    }
+
+    fn save<T>(&self, value: &T) -> Result<()>
+    where
+        T: serde::ser::Serialize,
+    {
+        let mut writer = self.builder.writer()?;
+        if self.pretty {
+            serde_json::to_writer_pretty(&mut writer, value)?;
+        } else {
+            serde_json::to_writer(&mut writer, value)?;
+        }
+        writer.finish()
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+// The futures are `Send` if those of the inner builder are
+#[allow(clippy::future_not_send)]
+impl<B> AsyncSerDe for Builder<B>
+where
+    B: AsyncRwBuilder,
+{
+    async fn async_load<T>(&self) -> Result<T>
+    where
+        T: for<'de> serde::de::Deserialize<'de>,
+    {
+        use tokio::io::AsyncReadExt;
+        let mut buffer = vec![];
+        let _ = self.builder.async_reader().await?.read_to_end(&mut buffer).await?;
+        Ok(serde_json::from_slice(&buffer)?)
+    }
+
+    async fn async_save<T>(&self, value: &T) -> Result<()>
+    where
+        T: serde::ser::Serialize,
+    {
+        use tokio::io::AsyncWriteExt;
+        let buffer = if self.pretty {
+            serde_json::to_vec_pretty(value)?
+        } else {
+            serde_json::to_vec(value)?
+        };
+        let mut writer = self.builder.async_writer().await?;
+        writer.write_all(&buffer).await?;
+        Ok(writer.shutdown().await?)
+    }
+}
@@ -25,7 +25,6 @@
     non_ascii_idents,
     noop_method_call,
     rust_2021_incompatible_or_patterns,
-    pointer_structural_match,
     semicolon_in_expressions_from_macros,
     single_use_lifetimes,
     trivial_casts,
@@ -60,8 +59,17 @@
     rustdoc::private_intra_doc_links
 )]
 
+use std::process::Command;
+
 use anyhow::Result;
 
+/// Provides the `ChaCha20Poly1305Builder` type which authenticates the stream
+/// while encrypting and decrypting it.
+#[cfg(feature = "chacha20poly1305")]
+mod aead;
+#[cfg(feature = "chacha20poly1305")]
+pub use crate::aead::{AeadError, Builder as ChaCha20Poly1305Builder, ChaCha20Poly1305Key};
+
 /// Provides the `BincodeBuilder` type which acts as a sink to (de)serialize a
 /// `&[u8]` as bincode.
 #[cfg(feature = "bincode")]
@@ -69,15 +77,78 @@ mod bincode;
 #[cfg(feature = "bincode")]
 pub use crate::bincode::Builder as BincodeBuilder;
 
+/// Provides the `Brotli` encoder and decoder builder which wraps the streaming
+/// compression provided by the brotli crate.
+#[cfg(feature = "brotli")]
+mod brotli;
+#[cfg(feature = "brotli")]
+pub use crate::brotli::{Brotli, Encoder as BrotliEncoder};
+
+/// Provides the `Bzip2` encoder and decoder builder which wraps the streaming
+/// compression provided by the bzip2 crate.
+#[cfg(feature = "bzip2")]
+mod bzip2;
+#[cfg(feature = "bzip2")]
+pub use crate::bzip2::Bzip2;
+
 /// Provides the `BufferedBuilder` type which helps build `BufReader` and
 /// `BufWriter` instances.
 mod buffered;
 pub use buffered::Builder as BufferedBuilder;
 
-/// Provides the `FileBuilder` type which acts as a source to read from and
-/// write to a file.
+/// Provides the `CompressionBuilder` type and the traits which the
+/// compression algorithms implement to be used through it.
+#[cfg(any(
+    feature = "brotli",
+    feature = "bzip2",
+    feature = "flate2",
+    feature = "lz4",
+    feature = "xz",
+    feature = "zstd"
+))]
+mod compression;
+#[cfg(all(
+    feature = "async-tokio",
+    any(
+        feature = "brotli",
+        feature = "bzip2",
+        feature = "flate2",
+        feature = "lz4",
+        feature = "xz",
+        feature = "zstd"
+    )
+))]
+pub use compression::AsyncCoderBuilder;
+#[cfg(any(
+    feature = "brotli",
+    feature = "bzip2",
+    feature = "flate2",
+    feature = "lz4",
+    feature = "xz",
+    feature = "zstd"
+))]
+pub use compression::{Codec, CoderBuilder, CompressionBuilder, Constructor};
+
+/// Provides the `DigestBuilder` type which wraps the cryptographic hash
+/// functions implementing the `Digest` trait of the digest crate.
+#[cfg(feature = "digest")]
+mod digest;
+#[cfg(feature = "digest")]
+pub use crate::digest::{
+    Builder as DigestBuilder, Hash, Reader as DigestReader, Writer as DigestWriter,
+};
+
+// The hash functions in the dev-dependencies are only used by the tests of
+// the digest feature.
+#[cfg(all(test, not(feature = "digest")))]
+use {blake3 as _, sha2 as _};
+
+/// Provides the `FileBuilder` and `AtomicFileBuilder` types which act as
+/// sources to read from and write to a file.
 mod file;
-pub use file::Builder as FileBuilder;
+pub use file::{
+    AtomicBuilder as AtomicFileBuilder, AtomicWriter as AtomicFileWriter, Builder as FileBuilder,
+};
 
 /// Provides several wrapper types around the streaming compression algorithms
 /// provided by the flate2 crate.
@@ -87,43 +158,204 @@ mod flate2;
 pub use ::flate2::Compression;
 
 #[cfg(feature = "flate2")]
-pub use crate::flate2::{CompressionBuilder, Constructor, CrcBuilder};
+pub use crate::flate2::{CrcBuilder, Deflate, Gz, Zlib};
+
+// The async compression transformations are only available when the flate2
+// feature is enabled as well.
+#[cfg(all(feature = "async-tokio", not(feature = "flate2")))]
+use async_compression as _;
+
+/// Provides the `JsonBuilder` type which acts as a sink to (de)serialize a
+/// `&[u8]` as JSON.
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "json")]
+pub use crate::json::Builder as JsonBuilder;
+
+/// Provides the `Lz4` encoder and decoder builder which wraps the LZ4 frame
+/// compression provided by the lz4 crate.
+#[cfg(feature = "lz4")]
+mod lz4;
+#[cfg(feature = "lz4")]
+pub use crate::lz4::{Encoder as Lz4Encoder, Lz4};
+
+/// Provides the `MmapFileBuilder` type which acts as a source to read from and
+/// write to a memory mapped file.
+#[cfg(feature = "mmap")]
+mod mmap_file;
+#[cfg(feature = "mmap")]
+pub use mmap_file::{
+    Builder as MmapFileBuilder, Reader as MmapFileReader, Writer as MmapFileWriter,
+};
+
+/// Provides the `PipeBuilder` type which transforms the bytes by piping them
+/// through external commands.
+mod pipe;
+pub use pipe::{Builder as PipeBuilder, Reader as PipeReader, Writer as PipeWriter};
 
 /// Provides the `ProcessBuilder` type which acts as a source to read from
 /// stdout and write to stdin of a running process.
 mod process;
-pub use process::Builder as ProcessBuilder;
+#[cfg(feature = "async-tokio")]
+pub use process::{AsyncReader as AsyncProcessReader, AsyncWriter as AsyncProcessWriter};
+pub use process::{
+    Builder as ProcessBuilder, ChildBuilder, ProcessError, Reader as ProcessReader,
+    Writer as ProcessWriter,
+};
 
-/// Provides several wrapper types around the streaming cipher algorithms
-/// provided by the flate2 crate.
-#[cfg(any(feature = "chacha20", feature = "salsa20"))]
+/// Provides the `RecordsBuilder` type which acts as a sink to stream records
+/// one at a time.
+#[cfg(any(feature = "bincode", feature = "json"))]
+mod records;
+#[cfg(feature = "bincode")]
+pub use records::LengthDelimited;
+#[cfg(feature = "json")]
+pub use records::Ndjson;
+#[cfg(any(feature = "bincode", feature = "json"))]
+pub use records::{
+    Builder as RecordsBuilder, Framing, Reader as RecordReader, Writer as RecordWriter,
+};
+
+/// Provides the `StreamCipherBuilder` type which wraps any stream cipher
+/// implementing the traits of the cipher crate, and aliases for the ciphers
+/// that can be enabled through features.
+#[cfg(feature = "cipher")]
 mod stream_cipher;
 #[cfg(feature = "chacha20")]
-pub use stream_cipher::{ChaCha20Builder, ChaCha20Key, ChaCha20Nonce};
+pub use ::chacha20::{ChaCha20, XChaCha20};
+#[cfg(feature = "salsa20")]
+pub use ::salsa20::Salsa20;
+#[cfg(feature = "aes")]
+pub use stream_cipher::{
+    Aes128Ctr, Aes128CtrBuilder, Aes128CtrKey, Aes256Ctr, Aes256CtrBuilder, Aes256CtrKey, AesCtrIv,
+};
+#[cfg(feature = "cipher")]
+pub use stream_cipher::{
+    Builder as StreamCipherBuilder, HeaderAlgorithm, HeaderBuilder as StreamCipherHeaderBuilder,
+    HeaderError, Reader as StreamCipherReader, Writer as StreamCipherWriter,
+};
+#[cfg(feature = "chacha20")]
+pub use stream_cipher::{
+    ChaCha20Builder, ChaCha20Key, ChaCha20Nonce, XChaCha20Builder, XChaCha20Nonce,
+};
 #[cfg(feature = "salsa20")]
 pub use stream_cipher::{Salsa20Builder, Salsa20Key, Salsa20Nonce};
 
+/// Provides the `StdioBuilder` and `StderrBuilder` types which act as sources to
+/// read from stdin and write to stdout or stderr of the current process.
+mod stdio;
+pub use stdio::{Builder as StdioBuilder, StderrBuilder};
+
 /// Provides the `StringBuilder` type which is a sink without serde
 mod string;
 pub use string::AdhocWriter;
 
-/// Provides the `TcpStreamBuilder` type which acts as a source to read from and
-/// write to a TCP stream.
+/// Provides the `SyncVecBuilder` type which acts as a source to read from and
+/// write to a memory buffer shared between threads.
+mod sync_vec;
+pub use sync_vec::Builder as SyncVecBuilder;
+
+/// Provides the `TcpListenerBuilder` type which acts as a source to read from
+/// and write to the connections accepted by a TCP listener.
+mod tcp_listener;
+pub use tcp_listener::Builder as TcpListenerBuilder;
+
+/// Provides the `TcpStreamBuilder` and `TcpConnectedBuilder` types which act as
+/// sources to read from and write to a TCP stream.
 mod tcp_stream;
-pub use tcp_stream::Builder as TcpStreamBuilder;
+pub use tcp_stream::{Builder as TcpStreamBuilder, ConnectedBuilder as TcpConnectedBuilder};
+
+/// Provides the `Xz` encoder and decoder builder which wraps the streaming
+/// LZMA2 compression provided by the xz2 crate.
+#[cfg(feature = "xz")]
+mod xz;
+#[cfg(feature = "xz")]
+pub use crate::xz::Xz;
+
+/// Provides the `Zstd` encoder and decoder builder which wraps the streaming
+/// compression provided by the zstd crate.
+#[cfg(feature = "zstd")]
+mod zstd;
+#[cfg(feature = "zstd")]
+pub use crate::zstd::{Encoder as ZstdEncoder, Zstd};
+
+/// Provides the `TextEncodingBuilder` type which encodes the bytes as
+/// printable text.
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+mod text_encoding;
+#[cfg(feature = "base64")]
+pub use ::base64::engine::{GeneralPurpose, general_purpose};
+#[cfg(feature = "base32")]
+pub use text_encoding::Base32;
+#[cfg(feature = "base64")]
+pub use text_encoding::Base64;
+#[cfg(feature = "hex")]
+pub use text_encoding::Hex;
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+pub use text_encoding::{
+    Builder as TextEncodingBuilder, Reader as TextDecoder, TextEncoding, Writer as TextEncoder,
+};
+
+/// Provides the `UnixStreamBuilder`, `UnixListenerBuilder` and
+/// `UnixDatagramBuilder` types which act as sources to read from and write to
+/// Unix domain sockets.
+#[cfg(unix)]
+mod unix_stream;
+#[cfg(unix)]
+pub use unix_stream::{
+    Builder as UnixStreamBuilder, DatagramBuilder as UnixDatagramBuilder, DatagramReader,
+    DatagramWriter, ListenerBuilder as UnixListenerBuilder,
+};
 
 /// Provides the `VecBuilder` type which acts as a source to read from and write
 /// to a memory buffer.
 mod vec;
 pub use vec::Builder as VecBuilder;
 
+/// Counter making the names of temporary files unique within the process
+static TEMP_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// A unique path for a temporary file next to `path`, which is meant to be
+/// renamed over it
+fn temp_path(path: &std::path::Path) -> std::path::PathBuf {
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(path.file_name().unwrap_or_default());
+    temp_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    ));
+    path.with_file_name(temp_name)
+}
+
+/// Calculate the position in a buffer of length `len` after seeking from
+/// `position`
+fn seek_position(position: usize, len: usize, seek: std::io::SeekFrom) -> std::io::Result<usize> {
+    let (base, offset) = match seek {
+        std::io::SeekFrom::Start(offset) => {
+            return usize::try_from(offset).map_err(std::io::Error::other);
+        }
+        std::io::SeekFrom::End(offset) => (len, offset),
+        std::io::SeekFrom::Current(offset) => (position, offset),
+    };
+    let distance = usize::try_from(offset.unsigned_abs()).map_err(std::io::Error::other)?;
+    if offset < 0 { base.checked_sub(distance) } else { base.checked_add(distance) }.ok_or_else(
+        || {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        },
+    )
+}
+
 /// The trait that can construct readers and writers, but also has chainable
 /// functions to create more complex builders
 pub trait RwBuilder
 where
     Self: Sized,
     Self::Reader: std::io::Read,
-    Self::Writer: std::io::Write,
+    Self::Writer: std::io::Write + Finish,
 {
     /// The reader type that will be constructed by the reader function
     type Reader;
@@ -149,6 +381,14 @@ where
         BufferedBuilder::new(self)
     }
 
+    /// Transformation that pipes the bytes through external commands, the
+    /// writer through `writer_command` and the reader through `reader_command`,
+    /// which should be each other's inverse. Background threads move the bytes
+    /// between the commands and the inner readers and writers.
+    fn pipe_through(self, writer_command: Command, reader_command: Command) -> PipeBuilder<Self> {
+        PipeBuilder::new(self, writer_command, reader_command)
+    }
+
     /// Sink that provides a bridge between `String` instances and underlying
     /// readers and writers.
     fn string(self) -> string::Builder<Self> {
@@ -162,6 +402,57 @@ where
         BincodeBuilder::new(self)
     }
 
+    /// Sink that provides a bridge between serde and the underlying readers and
+    /// writer by transforming from and to JSON.
+    #[cfg(feature = "json")]
+    fn json(self) -> JsonBuilder<Self> {
+        JsonBuilder::new(self)
+    }
+
+    /// Sink that streams records from and to the underlying readers and writers
+    /// as newline delimited JSON.
+    #[cfg(feature = "json")]
+    fn ndjson(self) -> RecordsBuilder<Self, Ndjson> {
+        RecordsBuilder::new(self)
+    }
+
+    /// Sink that streams records from and to the underlying readers and writers
+    /// as bincode prefixed by their length.
+    #[cfg(feature = "bincode")]
+    fn bincode_records(self) -> RecordsBuilder<Self, LengthDelimited> {
+        RecordsBuilder::new(self)
+    }
+
+    /// Transformation that decrypts while reading and encrypts while writing
+    /// using any stream cipher that can be created from a key and a nonce,
+    /// e.g. `ctr::Ctr64LE<aes::Aes192>`
+    #[cfg(feature = "cipher")]
+    fn stream_cipher<C>(
+        self,
+        key: ::cipher::Key<C>,
+        nonce: ::cipher::Iv<C>,
+    ) -> StreamCipherBuilder<Self, C>
+    where
+        C: ::cipher::KeyIvInit + ::cipher::StreamCipher,
+    {
+        StreamCipherBuilder::new(self, key, nonce)
+    }
+
+    /// Transformation that decrypts while reading and encrypts while writing
+    /// using a stream cipher like `XChaCha20` or `Aes256Ctr`, where every
+    /// writer picks a random nonce and writes it in a header in front of the
+    /// stream. The readers parse the header before decrypting.
+    #[cfg(feature = "cipher")]
+    fn stream_cipher_with_header<C>(
+        self,
+        key: ::cipher::Key<C>,
+    ) -> StreamCipherHeaderBuilder<Self, C>
+    where
+        C: ::cipher::KeyIvInit + ::cipher::StreamCipher + HeaderAlgorithm,
+    {
+        StreamCipherHeaderBuilder::new(self, key)
+    }
+
     /// Transformation that decrypts while reading and encrypts while writing
     /// using the chacha20 cipher
     #[cfg(feature = "chacha20")]
@@ -169,6 +460,14 @@ where
         ChaCha20Builder::<Self>::new(self, key, nonce)
     }
 
+    /// Transformation that decrypts while reading and encrypts while writing
+    /// using the xchacha20 cipher, whose extended nonce is large enough to be
+    /// chosen at random
+    #[cfg(feature = "chacha20")]
+    fn xchacha20(self, key: ChaCha20Key, nonce: XChaCha20Nonce) -> XChaCha20Builder<Self> {
+        XChaCha20Builder::<Self>::new(self, key, nonce)
+    }
+
     /// Transformation that decrypts while reading and encrypts while writing
     /// using the salsa20 cipher
     #[cfg(feature = "salsa20")]
@@ -176,6 +475,60 @@ where
         Salsa20Builder::<Self>::new(self, key, nonce)
     }
 
+    /// Transformation that decrypts while reading and encrypts while writing
+    /// using the AES-128 cipher in counter mode
+    #[cfg(feature = "aes")]
+    fn aes128_ctr(self, key: Aes128CtrKey, iv: AesCtrIv) -> Aes128CtrBuilder<Self> {
+        Aes128CtrBuilder::<Self>::new(self, key, iv)
+    }
+
+    /// Transformation that decrypts while reading and encrypts while writing
+    /// using the AES-256 cipher in counter mode
+    #[cfg(feature = "aes")]
+    fn aes256_ctr(self, key: Aes256CtrKey, iv: AesCtrIv) -> Aes256CtrBuilder<Self> {
+        Aes256CtrBuilder::<Self>::new(self, key, iv)
+    }
+
+    /// Transformation that authenticates and decrypts while reading and
+    /// encrypts while writing using the chacha20poly1305 cipher in segments.
+    /// Every writer starts the stream with a random nonce.
+    #[cfg(feature = "chacha20poly1305")]
+    fn aead_chacha20poly1305(self, key: ChaCha20Poly1305Key) -> ChaCha20Poly1305Builder<Self> {
+        ChaCha20Poly1305Builder::new(self, key)
+    }
+
+    /// Transformation that decodes while reading and encodes while writing
+    /// using base64, configured through an engine like
+    /// `general_purpose::STANDARD`
+    #[cfg(feature = "base64")]
+    fn base64(self, config: GeneralPurpose) -> TextEncodingBuilder<Self, Base64> {
+        TextEncodingBuilder::new(self, Base64(config))
+    }
+
+    /// Transformation that decodes while reading and encodes while writing
+    /// using lowercase hexadecimal digits
+    #[cfg(feature = "hex")]
+    fn hex(self) -> TextEncodingBuilder<Self, Hex> {
+        TextEncodingBuilder::new(self, Hex)
+    }
+
+    /// Transformation that decodes while reading and encodes while writing
+    /// using padded base32
+    #[cfg(feature = "base32")]
+    fn base32(self) -> TextEncodingBuilder<Self, Base32> {
+        TextEncodingBuilder::new(self, Base32)
+    }
+
+    /// Non-commutative transformation that hashes using a cryptographic hash
+    /// function implementing `Digest` of digest 0.10, like `sha2::Sha256`
+    #[cfg(feature = "digest")]
+    fn digest<D>(self) -> DigestBuilder<Self, D>
+    where
+        D: ::digest::Digest + Clone,
+    {
+        DigestBuilder::new(self)
+    }
+
     /// Non-commutative transformation that hashes using the CRC algorithm
     #[cfg(feature = "flate2")]
     fn crc(self) -> CrcBuilder<Self> {
@@ -185,28 +538,141 @@ where
     /// Transformation that decompresses while reading and compresses while
     /// writing using the Deflate algorithm
     #[cfg(feature = "flate2")]
-    fn deflate(self, compression: Compression) -> CompressionBuilder<Self, flate2::Deflate> {
-        flate2::Deflate::new(self, compression)
+    fn deflate(self, compression: Compression) -> CompressionBuilder<Self, Deflate> {
+        Deflate::new(self, compression)
     }
 
     /// Transformation that decompresses while reading and compresses while
     /// writing using the Gz algorithm
     #[cfg(feature = "flate2")]
-    fn gz(self, compression: Compression) -> CompressionBuilder<Self, flate2::Gz> {
-        flate2::Gz::new(self, compression)
+    fn gz(self, compression: Compression) -> CompressionBuilder<Self, Gz> {
+        Gz::new(self, compression)
     }
 
     /// Transformation that decompresses while reading and compresses while
     /// writing using the Zlib algorithm
     #[cfg(feature = "flate2")]
-    fn zlib(self, compression: Compression) -> CompressionBuilder<Self, flate2::Zlib> {
-        flate2::Zlib::new(self, compression)
+    fn zlib(self, compression: Compression) -> CompressionBuilder<Self, Zlib> {
+        Zlib::new(self, compression)
+    }
+
+    /// Transformation that decompresses while reading and compresses while
+    /// writing using the Brotli algorithm at the given quality between 0 and
+    /// 11, with a sliding window of `2^window` bytes where the window is
+    /// between 10 and 24
+    #[cfg(feature = "brotli")]
+    fn brotli(self, quality: u32, window: u32) -> CompressionBuilder<Self, Brotli> {
+        CompressionBuilder::with_coder(self, quality, Brotli::with_window(window))
+    }
+
+    /// Transformation that decompresses while reading and compresses while
+    /// writing using the bzip2 algorithm at the given level between 1 and 9.
+    /// Creating a writer fails for any other level.
+    #[cfg(feature = "bzip2")]
+    fn bzip2(self, level: u32) -> CompressionBuilder<Self, Bzip2> {
+        Bzip2::new(self, level)
+    }
+
+    /// Transformation that decompresses while reading and compresses while
+    /// writing using the xz format and LZMA2 algorithm at the given preset
+    /// between 0 and 9. Creating a writer fails for any other preset.
+    #[cfg(feature = "xz")]
+    fn xz(self, preset: u32) -> CompressionBuilder<Self, Xz> {
+        Xz::new(self, preset)
+    }
+
+    /// Transformation that decompresses while reading and compresses while
+    /// writing LZ4 frames at the given level, where zero is the fast default
+    #[cfg(feature = "lz4")]
+    fn lz4(self, level: u32) -> CompressionBuilder<Self, Lz4> {
+        Lz4::new(self, level)
+    }
+
+    /// Transformation that decompresses while reading and compresses while
+    /// writing using the Zstandard algorithm at the given level
+    #[cfg(feature = "zstd")]
+    fn zstd(self, level: i32) -> CompressionBuilder<Self, Zstd> {
+        Zstd::new(self, level)
+    }
+}
+
+/// Trait to explicitly finish a writer instead of relying on `Drop`.
+///
+/// Finishing a writer flushes it, writes any trailing data (like the trailer of
+/// a compressed stream) and then finishes the writer it wraps.
+pub trait Finish {
+    /// Finish this writer and all writers it wraps
+    /// # Errors
+    /// In case flushing or finalizing any of the writers in the chain fails
+    /// the error of the first one that failed is returned.
+    fn finish(self) -> Result<()>;
+}
+
+/// Opt-in extension of the `RwBuilder` trait for builders whose readers and
+/// writers can be repositioned through `std::io::Seek`
+pub trait SeekableRwBuilder: RwBuilder
+where
+    Self::Reader: std::io::Read + std::io::Seek,
+    Self::Writer: std::io::Write + Finish + std::io::Seek,
+{
+    /// Construct a reader from this builder which starts reading at `offset`
+    /// # Errors
+    /// In case the construction of any of the intermediate readers or seeking
+    /// fails this will return the error associated to the first one that
+    /// failed.
+    fn reader_at(&self, offset: u64) -> Result<Self::Reader> {
+        let mut reader = self.reader()?;
+        let _ = std::io::Seek::seek(&mut reader, std::io::SeekFrom::Start(offset))?;
+        Ok(reader)
+    }
+
+    /// Construct a writer from this builder which starts writing at `offset`
+    /// # Errors
+    /// In case the construction of any of the intermediate writers or seeking
+    /// fails this will return the error associated to the first one that
+    /// failed.
+    fn writer_at(&self, offset: u64) -> Result<Self::Writer> {
+        let mut writer = self.writer()?;
+        let _ = std::io::Seek::seek(&mut writer, std::io::SeekFrom::Start(offset))?;
+        Ok(writer)
     }
 }
 
+/// The async counterpart of the `RwBuilder` trait that constructs tokio
+/// `AsyncRead`ers and `AsyncWrite`rs
+///
+/// Builders are chained through the functions of the `RwBuilder` trait, e.g.
+/// `buffered` or `gz`, since the sources and transformations implementing this
+/// trait implement `RwBuilder` as well. The futures are only `Send` if the
+/// builders being chained are, e.g. those of a `VecBuilder` never are.
+#[cfg(feature = "async-tokio")]
+pub trait AsyncRwBuilder
+where
+    Self::AsyncReader: tokio::io::AsyncRead + Unpin,
+    Self::AsyncWriter: tokio::io::AsyncWrite + Unpin,
+{
+    /// The reader type that will be constructed by the async reader function
+    type AsyncReader;
+
+    /// Construct a reader from this builder
+    /// # Errors
+    /// In case the construction of any of the intermediate readers fails this
+    /// will return the error associated to the first one that failed.
+    fn async_reader(&self) -> impl std::future::Future<Output = Result<Self::AsyncReader>>;
+
+    /// The writer type that will be constructed by the async writer function
+    type AsyncWriter;
+
+    /// Construct a writer from this builder
+    /// # Errors
+    /// In case the construction of any of the intermediate writers fails this
+    /// will return the error associated to the first one that failed.
+    fn async_writer(&self) -> impl std::future::Future<Output = Result<Self::AsyncWriter>>;
+}
+
 /// Trait to wrap serialization and deserialization functionality behind uniform
 /// load and save functions
-#[cfg(feature = "bincode")]
+#[cfg(any(feature = "bincode", feature = "json"))]
 pub trait SerDe {
     /// Deserialize into a specified type
     /// # Errors
@@ -225,5 +691,26 @@ pub trait SerDe {
         T: serde::ser::Serialize;
 }
 
+/// The async counterpart of the `SerDe` trait
+#[cfg(all(feature = "async-tokio", any(feature = "bincode", feature = "json")))]
+pub trait AsyncSerDe {
+    /// Deserialize into a specified type
+    /// # Errors
+    /// In case the deserialization or the reading fails the return value will
+    /// contain the first error that occurred.
+    fn async_load<T>(&self) -> impl std::future::Future<Output = Result<T>>
+    where
+        T: for<'de> serde::de::Deserialize<'de>;
+
+    /// Serialize into the type of the last sink specified and shut the writer
+    /// down afterwards
+    /// # Errors
+    /// In case the serialization or the writing fails the return value will
+    /// contain the first error that occurred.
+    fn async_save<T>(&self, value: &T) -> impl std::future::Future<Output = Result<()>>
+    where
+        T: serde::ser::Serialize;
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,152 @@
+use std::{
+    fmt::{Debug, Formatter},
+    io::Write,
+};
+
+use anyhow::Result;
+use lz4::{BlockMode, ContentChecksum};
+
+use crate::{Codec, CoderBuilder, CompressionBuilder, Constructor, Finish};
+
+/// The LZ4 frame encoder and decoder builder, whose level is between 0 and 16
+/// where zero is the fast default
+#[derive(Debug, Copy, Clone)]
+pub struct Lz4 {
+    /// Whether the frame ends with a checksum of the uncompressed content
+    content_checksum: bool,
+    /// Whether every block can be decompressed without the blocks before it
+    block_independence: bool,
+}
+
+impl Default for Lz4 {
+    /// Like the `lz4` command line tool, write a content checksum and
+    /// independent blocks
+    fn default() -> Self {
+        Self { content_checksum: true, block_independence: true }
+    }
+}
+
+impl Lz4 {
+    /// Whether the encoder appends a checksum of the uncompressed content to
+    /// the frame, which the decoder verifies
+    #[must_use]
+    pub const fn content_checksum(self, content_checksum: bool) -> Self {
+        Self { content_checksum, ..self }
+    }
+
+    /// Whether the encoder compresses every block on its own, instead of
+    /// referring back to previous blocks for a better compression ratio
+    #[must_use]
+    pub const fn block_independence(self, block_independence: bool) -> Self {
+        Self { block_independence, ..self }
+    }
+}
+
+impl Codec for Lz4 {
+    type Level = u32;
+}
+
+impl<B> Constructor<B> for Lz4 {}
+
+impl<R, W> CoderBuilder<R, W> for Lz4
+where
+    R: std::io::Read,
+    W: Write,
+{
+    type Decoder = lz4::Decoder<R>;
+    type Encoder = Encoder<W>;
+
+    fn encoder(&self, writer: W, level: u32) -> std::io::Result<Self::Encoder> {
+        let checksum = if self.content_checksum {
+            ContentChecksum::ChecksumEnabled
+        } else {
+            ContentChecksum::NoChecksum
+        };
+        let block_mode =
+            if self.block_independence { BlockMode::Independent } else { BlockMode::Linked };
+        let encoder = lz4::EncoderBuilder::new()
+            .level(level)
+            .checksum(checksum)
+            .block_mode(block_mode)
+            .build(writer)?;
+        Ok(Encoder { encoder: Some(encoder) })
+    }
+
+    fn decoder(&self, reader: R) -> std::io::Result<Self::Decoder> {
+        lz4::Decoder::new(reader)
+    }
+}
+
+impl<B> CompressionBuilder<B, Lz4> {
+    /// Whether the frame ends with a content checksum, see
+    /// `Lz4::content_checksum`
+    #[must_use]
+    pub fn content_checksum(self, content_checksum: bool) -> Self {
+        self.map_coder(|lz4| lz4.content_checksum(content_checksum))
+    }
+
+    /// Whether the blocks are compressed on their own, see
+    /// `Lz4::block_independence`
+    #[must_use]
+    pub fn block_independence(self, block_independence: bool) -> Self {
+        self.map_coder(|lz4| lz4.block_independence(block_independence))
+    }
+}
+
+/// Writer that compresses into an LZ4 frame and, like the flate2 encoders,
+/// finishes the frame when dropped
+pub struct Encoder<W>
+where
+    W: Write,
+{
+    /// The LZ4 encoder, which is taken when the writer is finished
+    encoder: Option<lz4::Encoder<W>>,
+}
+
+impl<W> Debug for Encoder<W>
+where
+    W: Write + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let writer = self.encoder.as_ref().map(lz4::Encoder::writer);
+        f.debug_struct("Encoder").field("writer", &writer).finish_non_exhaustive()
+    }
+}
+
+impl<W> Write for Encoder<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.encoder.as_mut().map_or(Ok(0), |encoder| encoder.write(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.encoder.as_mut().map_or(Ok(()), Write::flush)
+    }
+}
+
+impl<W> Finish for Encoder<W>
+where
+    W: Write + Finish,
+{
+    fn finish(mut self) -> Result<()> {
+        self.encoder.take().map_or(Ok(()), |encoder| {
+            let (writer, result) = encoder.finish();
+            result?;
+            writer.finish()
+        })
+    }
+}
+
+impl<W> Drop for Encoder<W>
+where
+    W: Write,
+{
+    fn drop(&mut self) {
+        if let Some(encoder) = self.encoder.take() {
+            // Errors can't be reported while dropping, use `finish` instead
+            let _ = encoder.finish();
+        }
+    }
+}
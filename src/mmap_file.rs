@@ -0,0 +1,259 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use memmap2::{Mmap, MmapMut};
+
+use crate::{Finish, RwBuilder, SeekableRwBuilder, seek_position, temp_path};
+
+/// The capacity the writer's map starts with when no size was given
+const MIN_CAPACITY: usize = 4096;
+
+/// Type for building readers and writers on top of a memory mapped file.
+/// It is itself an `RwBuilder`, but can't be created through one.
+/// This is why we call it a source.
+///
+/// Writers map a temporary file which replaces the target once they are
+/// finished, so they never modify a file a reader has mapped. A writer that is
+/// dropped without being finished leaves the target untouched.
+#[derive(Debug)]
+pub struct Builder {
+    /// The path of the file for which readers and writers can be created
+    path: PathBuf,
+    /// The size the temporary file is grown to before the writer maps it
+    size: usize,
+}
+
+impl Builder {
+    /// Factory function to create a builder holding on to a file path
+    #[must_use]
+    pub const fn new(path: PathBuf) -> Self {
+        Self { path, size: 0 }
+    }
+
+    /// Pre-size the mapped region of the writer, which avoids remapping the
+    /// file while writing up to `size` bytes
+    #[must_use]
+    pub fn size(self, size: usize) -> Self {
+        Self { size, ..self }
+    }
+}
+
+/// Map the whole file for reading
+#[allow(unsafe_code)]
+fn map(file: &File) -> std::io::Result<Mmap> {
+    // SAFETY: The map is only sound as long as no one truncates or modifies
+    // the file while it is mapped. The writers of this module replace the file
+    // by renaming a new one over it, which leaves the mapped one untouched.
+    // Modifying the file in place by other means is the caller's
+    // responsibility just like for any other memory mapped file.
+    unsafe { Mmap::map(file) }
+}
+
+/// Map the whole file for writing
+#[allow(unsafe_code)]
+fn map_mut(file: &File) -> std::io::Result<MmapMut> {
+    // SAFETY: Only used on the writer's own temporary file, which nothing else
+    // opens before it is renamed and which the writer only resizes while it
+    // is not mapped.
+    unsafe { MmapMut::map_mut(file) }
+}
+
+impl RwBuilder for Builder {
+    type Reader = Reader;
+    type Writer = Writer;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        let file = File::open(&self.path)?;
+        Ok(Reader { mmap: map(&file)?, position: 0 })
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        let temp_path = temp_path(&self.path);
+        let file = OpenOptions::new().read(true).write(true).create_new(true).open(&temp_path)?;
+        let mut writer = Writer {
+            file: Some(file),
+            mmap: None,
+            position: 0,
+            length: 0,
+            temp_path,
+            path: self.path.clone(),
+        };
+        if self.size > 0 {
+            writer.remap(self.size)?;
+        }
+        Ok(writer)
+    }
+}
+
+impl SeekableRwBuilder for Builder {
+    /// The temporary file starts with a copy of the target, so the bytes that
+    /// aren't overwritten are kept
+    fn writer_at(&self, offset: u64) -> Result<Self::Writer> {
+        let mut writer = self.writer()?;
+        match File::open(&self.path) {
+            Ok(mut file) => {
+                let _ = std::io::copy(&mut file, &mut writer)?;
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+        let _ = writer.seek(SeekFrom::Start(offset))?;
+        Ok(writer)
+    }
+}
+
+/// Reading out of a memory map of the file
+#[derive(Debug)]
+pub struct Reader {
+    /// The map of the whole file
+    mmap: Mmap,
+    /// The read position in the map
+    position: usize,
+}
+
+impl Reader {
+    /// All bytes of the file, without copying them
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// The bytes of the file that weren't read yet, without copying them
+    #[must_use]
+    pub fn remaining(&self) -> &[u8] {
+        self.mmap.get(self.position..).unwrap_or_default()
+    }
+}
+
+impl AsRef<[u8]> for Reader {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Read for Reader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let byte_count = self.remaining().read(buf)?;
+        self.position += byte_count;
+        Ok(byte_count)
+    }
+}
+
+impl BufRead for Reader {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.remaining())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position += amt;
+    }
+}
+
+impl Seek for Reader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.position = seek_position(self.position, self.mmap.len(), pos)?;
+        u64::try_from(self.position).map_err(std::io::Error::other)
+    }
+}
+
+/// Writing into a memory map of a temporary file, which is grown and remapped
+/// when it's full.
+///
+/// The file is cut to the written length and renamed over the target when the
+/// writer is finished, dropping the writer discards it.
+#[derive(Debug)]
+pub struct Writer {
+    /// The file, which is taken when the writer is finished
+    file: Option<File>,
+    /// The map of the whole file, `None` as long as the file is empty
+    mmap: Option<MmapMut>,
+    /// The write position in the map
+    position: usize,
+    /// The number of bytes written, i.e. the length of the finished file
+    length: usize,
+    /// The path of the temporary file
+    temp_path: PathBuf,
+    /// The path of the target file
+    path: PathBuf,
+}
+
+impl Writer {
+    /// Grow the file to `capacity` bytes and map it again
+    fn remap(&mut self, capacity: usize) -> std::io::Result<()> {
+        drop(self.mmap.take());
+        let file = self.open_file()?;
+        file.set_len(u64::try_from(capacity).map_err(std::io::Error::other)?)?;
+        self.mmap = Some(map_mut(file)?);
+        Ok(())
+    }
+
+    /// The file, or an error once the writer is finished
+    fn open_file(&self) -> std::io::Result<&File> {
+        self.file.as_ref().ok_or_else(|| std::io::Error::other("the writer was already finished"))
+    }
+
+    /// Flush the map, cut the file to the written length and rename it over
+    /// the target
+    fn commit(&mut self) -> std::io::Result<()> {
+        if let Some(mmap) = self.mmap.take() {
+            mmap.flush()?;
+        }
+        let file = self.file.take().ok_or_else(|| std::io::Error::other("no file to commit"))?;
+        file.set_len(u64::try_from(self.length).map_err(std::io::Error::other)?)?;
+        drop(file);
+        std::fs::rename(&self.temp_path, &self.path)
+    }
+}
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let end = self.position.checked_add(buf.len()).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "writing past the maximum size of a memory map",
+            )
+        })?;
+        let capacity = self.mmap.as_ref().map_or(0, |mmap| mmap.len());
+        if end > capacity {
+            self.remap(end.max(capacity * 2).max(MIN_CAPACITY))?;
+        }
+        if let Some(mmap) = &mut self.mmap {
+            mmap[self.position..end].copy_from_slice(buf);
+        }
+        self.position = end;
+        self.length = self.length.max(end);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.mmap.as_ref().map_or(Ok(()), MmapMut::flush_async)
+    }
+}
+
+impl Seek for Writer {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.position = seek_position(self.position, self.length, pos)?;
+        u64::try_from(self.position).map_err(std::io::Error::other)
+    }
+}
+
+impl Finish for Writer {
+    fn finish(mut self) -> Result<()> {
+        Ok(self.commit()?)
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        // An unfinished writer may have failed halfway, so the target is only
+        // replaced by `finish`. Removing the temporary file fails once it is
+        // renamed.
+        drop(self.mmap.take());
+        drop(self.file.take());
+        let _ = std::fs::remove_file(&self.temp_path);
+    }
+}
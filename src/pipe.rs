@@ -0,0 +1,153 @@
+use std::{
+    io::{ErrorKind, Read, Write},
+    process::Command,
+    thread::JoinHandle,
+};
+
+use anyhow::{Result, anyhow};
+
+use crate::{Finish, ProcessBuilder, ProcessReader, ProcessWriter, RwBuilder};
+
+/// Type returned by the `pipe_through` function on the `RwBuilder` trait.
+/// It is itself an `RwBuilder` so can be chained further.
+#[derive(Debug)]
+#[allow(clippy::struct_field_names)]
+pub struct Builder<B> {
+    /// The inner builder it wraps
+    builder: B,
+    /// Spawns the command which transforms the bytes that are written
+    writer_process: ProcessBuilder,
+    /// Spawns the command which transforms the bytes that are read
+    reader_process: ProcessBuilder,
+}
+
+impl<B> Builder<B> {
+    /// Factory function to wrap an inner builder
+    #[must_use]
+    pub fn new(builder: B, writer_command: Command, reader_command: Command) -> Self {
+        Self {
+            builder,
+            writer_process: ProcessBuilder::new(writer_command),
+            reader_process: ProcessBuilder::new(reader_command),
+        }
+    }
+}
+
+impl<B> RwBuilder for Builder<B>
+where
+    B: RwBuilder,
+    B::Reader: Send + 'static,
+    B::Writer: Finish + Send + 'static,
+{
+    type Reader = Reader;
+    type Writer = Writer<B::Writer>;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        let mut reader = self.builder.reader()?;
+        let child = self.reader_process.spawn()?;
+        let mut stdin = child.writer()?;
+        let stdout = child.reader()?;
+        let feeder = std::thread::spawn(move || {
+            let _ = std::io::copy(&mut reader, &mut stdin)?;
+            stdin.flush()
+        });
+        Ok(Reader { stdout, feeder: Some(feeder) })
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        let mut writer = self.builder.writer()?;
+        let child = self.writer_process.spawn()?;
+        let stdin = child.writer()?;
+        let mut stdout = child.reader()?;
+        let forwarder = std::thread::spawn(move || {
+            let _ = std::io::copy(&mut stdout, &mut writer)?;
+            Ok(writer)
+        });
+        Ok(Writer { stdin: Some(stdin), forwarder: Some(forwarder) })
+    }
+}
+
+/// Reader of the output of a command, which a background thread feeds with
+/// the bytes of the inner reader
+#[derive(Debug)]
+pub struct Reader {
+    /// The stdout of the command
+    stdout: ProcessReader,
+    /// The thread writing the inner reader to the stdin of the command
+    feeder: Option<JoinHandle<std::io::Result<()>>>,
+}
+
+impl Read for Reader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let byte_count = self.stdout.read(buf)?;
+        if byte_count == 0 && !buf.is_empty() {
+            if let Some(feeder) = self.feeder.take() {
+                match feeder.join() {
+                    // The command may exit successfully without reading all
+                    // of its input
+                    Ok(Err(e)) if e.kind() != ErrorKind::BrokenPipe => return Err(e),
+                    Err(_) => return Err(std::io::Error::other("the feeding thread panicked")),
+                    _ => {}
+                }
+            }
+        }
+        Ok(byte_count)
+    }
+}
+
+/// Writer to the input of a command, whose output a background thread
+/// forwards to the inner writer
+#[derive(Debug)]
+pub struct Writer<W> {
+    /// The stdin of the command, which is taken when the writer is finished
+    stdin: Option<ProcessWriter>,
+    /// The thread writing the stdout of the command to the inner writer,
+    /// which hands back the inner writer when the command is done
+    forwarder: Option<JoinHandle<std::io::Result<W>>>,
+}
+
+impl<W> Writer<W> {
+    /// The stdin of the command, or an error once the writer is finished
+    fn stdin(&mut self) -> std::io::Result<&mut ProcessWriter> {
+        self.stdin.as_mut().ok_or_else(|| std::io::Error::other("the command stdin was closed"))
+    }
+
+    /// Close the input of the command and wait until all of its output was
+    /// forwarded
+    fn close(&mut self) -> Result<Option<W>> {
+        if let Some(stdin) = self.stdin.take() {
+            stdin.finish()?;
+        }
+        match self.forwarder.take().map(JoinHandle::join) {
+            Some(Ok(result)) => Ok(Some(result?)),
+            Some(Err(_)) => Err(anyhow!("the forwarding thread panicked")),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<W> Write for Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.stdin()?.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.stdin()?.flush()
+    }
+}
+
+impl<W> Finish for Writer<W>
+where
+    W: Finish,
+{
+    fn finish(mut self) -> Result<()> {
+        self.close()?.map_or(Ok(()), Finish::finish)
+    }
+}
+
+impl<W> Drop for Writer<W> {
+    fn drop(&mut self) {
+        // Errors can't be reported while dropping, use `finish` instead
+        let _ = self.close();
+    }
+}
@@ -1,11 +1,23 @@
 use std::{
     cell::RefCell,
-    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    fmt::{Display, Formatter},
+    io::{Read, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, ExitStatus, Stdio},
+    sync::{Arc, Mutex, MutexGuard},
+    thread::JoinHandle,
+};
+#[cfg(feature = "async-tokio")]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, ready},
 };
 
-use anyhow::{anyhow, Result};
+use anyhow::{Result, anyhow};
 
-use crate::RwBuilder;
+#[cfg(feature = "async-tokio")]
+use crate::AsyncRwBuilder;
+use crate::{Finish, RwBuilder};
 
 /// Type for building readers and writers on top of a process handle.
 /// It is itself an `RwBuilder`, but can't be created through one.
@@ -19,7 +31,8 @@ pub struct Builder {
 
 impl Builder {
     /// Create a builder that spawns a process based on the command being
-    /// passed.
+    /// passed. The stdio of the command is set up for every spawned process:
+    /// the streams that aren't piped are inherited.
     #[must_use]
     pub fn new(command: Command) -> Self {
         Self { command: command.into() }
@@ -32,24 +45,42 @@ impl Builder {
     /// # Errors
     /// Propagates the error of failing to spawn a child process
     pub fn spawn(&self) -> Result<ChildBuilder> {
-        let child =
-            self.command.borrow_mut().stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
-        Ok(ChildBuilder { child: child.into() })
+        let mut command = self.command.borrow_mut();
+        Ok(ChildBuilder { process: Process::spawn(&mut command, Stdio::piped(), Stdio::piped())? })
+    }
+}
+
+impl Builder {
+    /// Spawn a child process with only its stdout piped
+    fn spawn_reader(&self) -> Result<(ChildStdout, Arc<Mutex<Process>>)> {
+        let mut command = self.command.borrow_mut();
+        let process = Process::spawn(&mut command, Stdio::inherit(), Stdio::piped())?;
+        let stdout =
+            lock(&process)?.child.stdout.take().ok_or_else(|| anyhow!("no child stdout"))?;
+        Ok((stdout, process))
+    }
+
+    /// Spawn a child process with only its stdin piped
+    fn spawn_writer(&self) -> Result<(ChildStdin, Arc<Mutex<Process>>)> {
+        let mut command = self.command.borrow_mut();
+        let process = Process::spawn(&mut command, Stdio::piped(), Stdio::inherit())?;
+        let stdin = lock(&process)?.child.stdin.take().ok_or_else(|| anyhow!("no child stdin"))?;
+        Ok((stdin, process))
     }
 }
 
 impl RwBuilder for Builder {
-    type Reader = ChildStdout;
-    type Writer = ChildStdin;
+    type Reader = Reader;
+    type Writer = Writer;
 
     fn reader(&self) -> Result<Self::Reader> {
-        let mut child = self.command.borrow_mut().stdout(Stdio::piped()).spawn()?;
-        child.stdout.take().ok_or_else(|| anyhow!("no child stdout"))
+        let (stdout, process) = self.spawn_reader()?;
+        Ok(Reader { stdout, process })
     }
 
     fn writer(&self) -> Result<Self::Writer> {
-        let mut child = self.command.borrow_mut().stdin(Stdio::piped()).spawn()?;
-        child.stdin.take().ok_or_else(|| anyhow!("no child stdin"))
+        let (stdin, process) = self.spawn_writer()?;
+        Ok(Writer { stdin: Some(stdin), process, wait: true })
     }
 }
 
@@ -59,26 +90,380 @@ impl RwBuilder for Builder {
 #[derive(Debug)]
 pub struct ChildBuilder {
     /// Handle to the child process
-    child: RefCell<Child>,
+    process: Arc<Mutex<Process>>,
 }
 
-impl RwBuilder for ChildBuilder {
-    type Reader = ChildStdout;
-    type Writer = ChildStdin;
-
-    fn reader(&self) -> Result<Self::Reader> {
-        self.child
-            .borrow_mut()
+impl ChildBuilder {
+    /// Take the stdout of the child, which can only be done once
+    fn take_stdout(&self) -> Result<ChildStdout> {
+        lock(&self.process)?
+            .child
             .stdout
             .take()
             .ok_or_else(|| anyhow!("No child stdout. Did you already build a reader?"))
     }
 
-    fn writer(&self) -> Result<Self::Writer> {
-        self.child
-            .borrow_mut()
+    /// Take the stdin of the child, which can only be done once
+    fn take_stdin(&self) -> Result<ChildStdin> {
+        lock(&self.process)?
+            .child
             .stdin
             .take()
             .ok_or_else(|| anyhow!("No child stdin. Did you already build a writer?"))
     }
 }
+
+impl RwBuilder for ChildBuilder {
+    type Reader = Reader;
+    type Writer = Writer;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        Ok(Reader { stdout: self.take_stdout()?, process: Arc::clone(&self.process) })
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        // The reader waits for the child, finishing the writer only closes the
+        // input so the child can produce the rest of its output
+        Ok(Writer {
+            stdin: Some(self.take_stdin()?),
+            process: Arc::clone(&self.process),
+            wait: false,
+        })
+    }
+}
+
+/// The error reported when a child process exits unsuccessfully
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessError {
+    /// The exit status of the child process
+    pub status: ExitStatus,
+    /// Everything the child process wrote to its stderr
+    pub stderr: Vec<u8>,
+}
+
+impl Display for ProcessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the child process failed with {}", self.status)?;
+        let stderr = String::from_utf8_lossy(&self.stderr);
+        let stderr = stderr.trim();
+        if stderr.is_empty() { Ok(()) } else { write!(f, ": {stderr}") }
+    }
+}
+
+impl std::error::Error for ProcessError {}
+
+impl From<ProcessError> for std::io::Error {
+    fn from(error: ProcessError) -> Self {
+        Self::other(error)
+    }
+}
+
+/// A spawned child process whose stderr is captured in the background
+#[derive(Debug)]
+struct Process {
+    /// Handle to the child process
+    child: Child,
+    /// The thread collecting the stderr of the child, until it is joined
+    stderr_thread: Option<JoinHandle<Vec<u8>>>,
+    /// The exit status, once the child was waited on
+    status: Option<ExitStatus>,
+}
+
+impl Process {
+    /// Spawn the command with the given stdin and stdout, and its stderr
+    /// captured. All three are set for every spawn, so the configuration of
+    /// an earlier spawn doesn't leak into this one.
+    fn spawn(command: &mut Command, stdin: Stdio, stdout: Stdio) -> Result<Arc<Mutex<Self>>> {
+        let mut child = command.stdin(stdin).stdout(stdout).stderr(Stdio::piped()).spawn()?;
+        let stderr_thread = child.stderr.take().map(|mut stderr| {
+            std::thread::spawn(move || {
+                let mut buffer = vec![];
+                // Whatever was captured before a read error is still reported
+                let _ = stderr.read_to_end(&mut buffer);
+                buffer
+            })
+        });
+        Ok(Arc::new(Mutex::new(Self { child, stderr_thread, status: None })))
+    }
+
+    /// Close the input of the child, wait for it to exit and report a
+    /// `ProcessError` if it failed
+    fn wait(&mut self) -> std::io::Result<()> {
+        if self.status.is_none() {
+            drop(self.child.stdin.take());
+            self.status = Some(self.child.wait()?);
+        }
+        let Some(status) = self.status.filter(|status| !status.success()) else {
+            return Ok(());
+        };
+        let stderr = self.stderr_thread.take().and_then(|thread| thread.join().ok());
+        Err(ProcessError { status, stderr: stderr.unwrap_or_default() }.into())
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        if self.status.is_none() {
+            // The child wasn't waited on, e.g. because a reader was dropped
+            // before the end of the output, and may never exit on its own. Kill
+            // and reap it so it doesn't linger as a zombie, errors can't be
+            // reported while dropping.
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+/// Lock the shared process, turning a poisoned mutex into an I/O error
+fn lock(process: &Mutex<Process>) -> std::io::Result<MutexGuard<'_, Process>> {
+    process.lock().map_err(|_| std::io::Error::other("the process mutex was poisoned"))
+}
+
+/// The task waiting for a child process without blocking the async runtime
+#[cfg(feature = "async-tokio")]
+type WaitTask = tokio::task::JoinHandle<std::io::Result<()>>;
+
+/// Wait for the child on a blocking thread and report a `ProcessError` if it
+/// failed, `task` holds the wait in progress between polls
+#[cfg(feature = "async-tokio")]
+fn poll_wait(
+    process: &Arc<Mutex<Process>>,
+    task: &mut Option<WaitTask>,
+    cx: &mut Context<'_>,
+) -> Poll<std::io::Result<()>> {
+    let wait = task.get_or_insert_with(|| {
+        let process = Arc::clone(process);
+        tokio::task::spawn_blocking(move || lock(&process)?.wait())
+    });
+    let result = ready!(Pin::new(wait).poll(cx));
+    *task = None;
+    Poll::Ready(result.unwrap_or_else(|error| Err(error.into())))
+}
+
+/// Reading the stdout of a child process
+///
+/// At the end of the output the child is waited on, and a read fails with a
+/// `ProcessError` if it exited unsuccessfully. Dropping the reader before the
+/// end of the output kills the child.
+#[derive(Debug)]
+pub struct Reader {
+    /// The stdout of the child, dropped before the child is reaped
+    stdout: ChildStdout,
+    /// The child process, shared with the writer of a `ChildBuilder`
+    process: Arc<Mutex<Process>>,
+}
+
+impl Read for Reader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let byte_count = self.stdout.read(buf)?;
+        if byte_count == 0 && !buf.is_empty() {
+            lock(&self.process)?.wait()?;
+        }
+        Ok(byte_count)
+    }
+}
+
+/// Writing to the stdin of a child process
+///
+/// Finishing the writer closes the input of the child. A writer built by a
+/// `Builder` also waits for the child and fails with a `ProcessError` if it
+/// exited unsuccessfully. Dropping the writer does the same without reporting
+/// errors.
+#[derive(Debug)]
+pub struct Writer {
+    /// The stdin of the child, which is taken when the writer is finished
+    stdin: Option<ChildStdin>,
+    /// The child process, shared with the reader of a `ChildBuilder`
+    process: Arc<Mutex<Process>>,
+    /// Whether finishing the writer waits for the child to exit
+    wait: bool,
+}
+
+impl Writer {
+    /// The stdin of the child, or an error once the writer is finished
+    fn stdin(&mut self) -> std::io::Result<&mut ChildStdin> {
+        self.stdin.as_mut().ok_or_else(|| std::io::Error::other("the child stdin was closed"))
+    }
+}
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.stdin()?.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.stdin()?.flush()
+    }
+}
+
+impl Finish for Writer {
+    fn finish(mut self) -> Result<()> {
+        if let Some(stdin) = self.stdin.take() {
+            stdin.finish()?;
+        }
+        if self.wait {
+            lock(&self.process)?.wait()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        if let Some(stdin) = self.stdin.take() {
+            // Closing the input lets the child exit on its own, instead of it
+            // being killed once the process is dropped
+            drop(stdin);
+            if self.wait {
+                // Errors can't be reported while dropping, use `finish` instead
+                let _ = lock(&self.process).map(|mut process| process.wait());
+            }
+        }
+    }
+}
+
+impl Finish for ChildStdin {
+    /// Flushes the pipe and closes it so the child process reads the end of
+    /// its input.
+    fn finish(mut self) -> Result<()> {
+        Ok(self.flush()?)
+    }
+}
+
+/// Reading the stdout of a child process asynchronously
+///
+/// At the end of the output the child is waited on, and a read fails with a
+/// `ProcessError` if it exited unsuccessfully. Dropping the reader before the
+/// end of the output kills the child.
+#[cfg(feature = "async-tokio")]
+#[derive(Debug)]
+pub struct AsyncReader {
+    /// The stdout of the child
+    stdout: tokio::process::ChildStdout,
+    /// The child process, shared with the writer of a `ChildBuilder`
+    process: Arc<Mutex<Process>>,
+    /// The wait for the child once its output ended
+    task: Option<WaitTask>,
+}
+
+#[cfg(feature = "async-tokio")]
+impl tokio::io::AsyncRead for AsyncReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        if this.task.is_none() {
+            let filled = buf.filled().len();
+            ready!(Pin::new(&mut this.stdout).poll_read(cx, buf))?;
+            if buf.filled().len() > filled || buf.remaining() == 0 {
+                return Poll::Ready(Ok(()));
+            }
+        }
+        poll_wait(&this.process, &mut this.task, cx)
+    }
+}
+
+/// Writing to the stdin of a child process asynchronously
+///
+/// Shutting the writer down closes the input of the child. A writer built by a
+/// `Builder` also waits for the child and fails with a `ProcessError` if it
+/// exited unsuccessfully. Dropping a writer built by a `Builder` before it was
+/// shut down kills the child, as waiting would block the async runtime.
+#[cfg(feature = "async-tokio")]
+#[derive(Debug)]
+pub struct AsyncWriter {
+    /// The stdin of the child, which is dropped when the writer is shut down
+    stdin: Option<tokio::process::ChildStdin>,
+    /// The child process, shared with the reader of a `ChildBuilder`
+    process: Arc<Mutex<Process>>,
+    /// Whether shutting the writer down waits for the child to exit
+    wait: bool,
+    /// The wait for the child once its input was closed
+    task: Option<WaitTask>,
+}
+
+#[cfg(feature = "async-tokio")]
+impl AsyncWriter {
+    /// The stdin of the child, or an error once the writer is shut down
+    fn stdin(&mut self) -> std::io::Result<Pin<&mut tokio::process::ChildStdin>> {
+        self.stdin
+            .as_mut()
+            .map(Pin::new)
+            .ok_or_else(|| std::io::Error::other("the child stdin was closed"))
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl tokio::io::AsyncWrite for AsyncWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.get_mut().stdin()?.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        if this.stdin.is_none() {
+            return Poll::Ready(Ok(()));
+        }
+        this.stdin()?.poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        if let Some(stdin) = &mut this.stdin {
+            ready!(Pin::new(stdin).poll_shutdown(cx))?;
+            // Dropping the pipe closes it, so the child reads the end of its
+            // input
+            this.stdin = None;
+        }
+        if this.wait { poll_wait(&this.process, &mut this.task, cx) } else { Poll::Ready(Ok(())) }
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl AsyncRwBuilder for Builder {
+    type AsyncReader = AsyncReader;
+    type AsyncWriter = AsyncWriter;
+
+    // The child is spawned before the future is created, which keeps the
+    // `Command` that isn't `Sync` out of it, so the futures are `Send`
+    fn async_reader(&self) -> impl Future<Output = Result<Self::AsyncReader>> {
+        let spawned = self.spawn_reader();
+        async move {
+            let (stdout, process) = spawned?;
+            let stdout = tokio::process::ChildStdout::from_std(stdout)?;
+            Ok(AsyncReader { stdout, process, task: None })
+        }
+    }
+
+    fn async_writer(&self) -> impl Future<Output = Result<Self::AsyncWriter>> {
+        let spawned = self.spawn_writer();
+        async move {
+            let (stdin, process) = spawned?;
+            let stdin = Some(tokio::process::ChildStdin::from_std(stdin)?);
+            Ok(AsyncWriter { stdin, process, wait: true, task: None })
+        }
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl AsyncRwBuilder for ChildBuilder {
+    type AsyncReader = AsyncReader;
+    type AsyncWriter = AsyncWriter;
+
+    async fn async_reader(&self) -> Result<Self::AsyncReader> {
+        let stdout = tokio::process::ChildStdout::from_std(self.take_stdout()?)?;
+        Ok(AsyncReader { stdout, process: Arc::clone(&self.process), task: None })
+    }
+
+    async fn async_writer(&self) -> Result<Self::AsyncWriter> {
+        // Like the sync writer, only the reader waits for the child
+        let stdin = Some(tokio::process::ChildStdin::from_std(self.take_stdin()?)?);
+        Ok(AsyncWriter { stdin, process: Arc::clone(&self.process), wait: false, task: None })
+    }
+}
@@ -0,0 +1,216 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    marker::PhantomData,
+};
+
+use anyhow::Result;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{Finish, RwBuilder};
+
+/// Type returned by the `ndjson` and `bincode_records` functions on the
+/// `RwBuilder` trait. It is itself not an `RwBuilder` so can't be chained
+/// further. This is why we call it a sink.
+#[derive(Debug)]
+pub struct Builder<B, F> {
+    /// The inner builder it wraps
+    builder: B,
+    /// The framing used to separate the records in the stream
+    _marker: PhantomData<F>,
+}
+
+impl<B, F> Builder<B, F>
+where
+    B: RwBuilder,
+    F: Framing,
+{
+    /// Factory function to wrap an inner builder
+    #[must_use]
+    pub const fn new(builder: B) -> Self {
+        Self { builder, _marker: PhantomData }
+    }
+
+    /// Construct an iterator which deserializes one record at a time
+    /// # Errors
+    /// In case the construction of any of the intermediate readers fails this
+    /// will return the error associated to the first one that failed.
+    pub fn records<T>(&self) -> Result<Reader<B::Reader, F, T>>
+    where
+        T: DeserializeOwned,
+    {
+        let reader = BufReader::new(self.builder.reader()?);
+        Ok(Reader { reader, buffer: vec![], done: false, _marker: PhantomData })
+    }
+
+    /// Construct a writer which serializes one record at a time
+    /// # Errors
+    /// In case the construction of any of the intermediate writers fails this
+    /// will return the error associated to the first one that failed.
+    pub fn record_writer(&self) -> Result<Writer<B::Writer, F>>
+    where
+        B::Writer: Finish,
+    {
+        Ok(Writer { writer: self.builder.writer()?, _marker: PhantomData })
+    }
+}
+
+/// Implementors like `Ndjson` and `LengthDelimited` define how records are
+/// separated from each other in a stream.
+pub trait Framing {
+    /// Read the next record, or `None` at the end of the stream, using
+    /// `buffer` as scratch space
+    /// # Errors
+    /// In case the reading or the deserialization fails the error is
+    /// propagated.
+    fn read_record<R, T>(reader: &mut R, buffer: &mut Vec<u8>) -> Result<Option<T>>
+    where
+        R: BufRead,
+        T: DeserializeOwned;
+
+    /// Write a single record
+    /// # Errors
+    /// In case the serialization or the writing fails the error is propagated.
+    fn write_record<W, T>(writer: &mut W, value: &T) -> Result<()>
+    where
+        W: Write,
+        T: Serialize;
+}
+
+/// Newline delimited JSON, i.e. one JSON value per line
+#[cfg(feature = "json")]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Ndjson;
+
+#[cfg(feature = "json")]
+impl Framing for Ndjson {
+    fn read_record<R, T>(reader: &mut R, buffer: &mut Vec<u8>) -> Result<Option<T>>
+    where
+        R: BufRead,
+        T: DeserializeOwned,
+    {
+        loop {
+            buffer.clear();
+            if reader.read_until(b'\n', buffer)? == 0 {
+                return Ok(None);
+            }
+            if !buffer.iter().all(u8::is_ascii_whitespace) {
+                return Ok(Some(serde_json::from_slice(buffer)?));
+            }
+        }
+    }
+
+    fn write_record<W, T>(writer: &mut W, value: &T) -> Result<()>
+    where
+        W: Write,
+        T: Serialize,
+    {
+        serde_json::to_writer(&mut *writer, value)?;
+        Ok(writer.write_all(b"\n")?)
+    }
+}
+
+/// Bincode records which are each prefixed by their length as a little endian
+/// `u64`
+#[cfg(feature = "bincode")]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct LengthDelimited;
+
+#[cfg(feature = "bincode")]
+impl Framing for LengthDelimited {
+    fn read_record<R, T>(reader: &mut R, buffer: &mut Vec<u8>) -> Result<Option<T>>
+    where
+        R: BufRead,
+        T: DeserializeOwned,
+    {
+        if reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        let mut length = [0u8; 8];
+        reader.read_exact(&mut length)?;
+        let length = u64::from_le_bytes(length);
+        // The buffer only grows with the bytes actually read, so a corrupt
+        // length can't make it allocate more than the stream holds
+        buffer.clear();
+        let byte_count = reader.by_ref().take(length).read_to_end(buffer)?;
+        if u64::try_from(byte_count)? < length {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(Some(bincode::deserialize(buffer)?))
+    }
+
+    fn write_record<W, T>(writer: &mut W, value: &T) -> Result<()>
+    where
+        W: Write,
+        T: Serialize,
+    {
+        writer.write_all(&bincode::serialized_size(value)?.to_le_bytes())?;
+        Ok(bincode::serialize_into(writer, value)?)
+    }
+}
+
+/// Iterator over the records deserialized from a reader
+#[derive(Debug)]
+#[allow(clippy::struct_field_names)]
+pub struct Reader<R, F, T> {
+    /// The wrapped reader
+    reader: BufReader<R>,
+    /// Scratch space for reading a single record
+    buffer: Vec<u8>,
+    /// Whether the end of the stream or an error was encountered
+    done: bool,
+    /// We need `Reader` to be generic over the framing and the record type
+    _marker: PhantomData<fn() -> (F, T)>,
+}
+
+impl<R, F, T> Iterator for Reader<R, F, T>
+where
+    R: Read,
+    F: Framing,
+    T: DeserializeOwned,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let record = F::read_record(&mut self.reader, &mut self.buffer).transpose();
+        self.done = !matches!(record, Some(Ok(_)));
+        record
+    }
+}
+
+/// Writer that serializes records one at a time
+#[derive(Debug)]
+#[allow(clippy::struct_field_names)]
+pub struct Writer<W, F> {
+    /// The wrapped writer
+    writer: W,
+    /// We need `Writer` to be generic over the framing
+    _marker: PhantomData<F>,
+}
+
+impl<W, F> Writer<W, F>
+where
+    W: Write + Finish,
+    F: Framing,
+{
+    /// Serialize a single record to the stream
+    /// # Errors
+    /// In case the serialization or the writing fails the error is propagated.
+    pub fn push<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        F::write_record(&mut self.writer, value)
+    }
+}
+
+impl<W, F> Finish for Writer<W, F>
+where
+    W: Write + Finish,
+{
+    fn finish(self) -> Result<()> {
+        self.writer.finish()
+    }
+}
@@ -0,0 +1,62 @@
+use std::io::{StderrLock, StdinLock, StdoutLock, Write};
+
+use anyhow::Result;
+
+use crate::{Finish, RwBuilder};
+
+/// Type for building readers and writers on top of the standard input and
+/// output of the current process.
+///
+/// The reader and writer hold the lock on stdin and stdout respectively, so
+/// other threads block on them until they're dropped. It is itself an
+/// `RwBuilder`, but can't be created through one. This is why we call it a
+/// source.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Builder;
+
+impl RwBuilder for Builder {
+    type Reader = StdinLock<'static>;
+    type Writer = StdoutLock<'static>;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        Ok(std::io::stdin().lock())
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        Ok(std::io::stdout().lock())
+    }
+}
+
+/// Type for building readers on top of the standard input and writers on top
+/// of the standard error of the current process.
+///
+/// Useful for diagnostics which shouldn't end up in the output of a filter
+/// program. It is itself an `RwBuilder`, but can't be created through one.
+/// This is why we call it a source.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StderrBuilder;
+
+impl RwBuilder for StderrBuilder {
+    type Reader = StdinLock<'static>;
+    type Writer = StderrLock<'static>;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        Ok(std::io::stdin().lock())
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        Ok(std::io::stderr().lock())
+    }
+}
+
+impl Finish for StdoutLock<'_> {
+    fn finish(mut self) -> Result<()> {
+        Ok(self.flush()?)
+    }
+}
+
+impl Finish for StderrLock<'_> {
+    fn finish(mut self) -> Result<()> {
+        Ok(self.flush()?)
+    }
+}
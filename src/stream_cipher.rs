@@ -1,44 +1,57 @@
 use std::{
-    io::{Read, Write},
+    fmt::{Display, Formatter},
+    io::{Read, Seek, SeekFrom, Write},
     marker::PhantomData,
 };
+#[cfg(feature = "async-tokio")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
 
 use anyhow::Result;
 #[cfg(feature = "chacha20")]
-use chacha20::ChaCha20;
-use cipher::{KeyIvInit, StreamCipher};
+use chacha20::{ChaCha20, XChaCha20};
+use cipher::{Iv, Key, KeyIvInit, StreamCipher, StreamCipherSeek};
 #[cfg(feature = "salsa20")]
 use salsa20::Salsa20;
 
-use crate::RwBuilder;
+#[cfg(feature = "async-tokio")]
+use crate::AsyncRwBuilder;
+use crate::{Finish, RwBuilder, SeekableRwBuilder};
 
-/// Type returned by the `chacha20` and `salsa20` functions on the `RwBuilder`
-/// trait. It is itself an `RwBuilder` so can be chained further.
+/// Type returned by the `stream_cipher`, `chacha20` and `salsa20` functions on
+/// the `RwBuilder` trait. It is itself an `RwBuilder` so can be chained
+/// further, and an `AsyncRwBuilder` on top of one.
 #[derive(Debug)]
-pub struct Builder<B, C, K, N>
+#[allow(clippy::struct_field_names)]
+pub struct Builder<B, C>
 where
-    B: RwBuilder,
-    C: StreamCipher,
+    C: KeyIvInit,
 {
     /// The inner builder it wraps
     builder: B,
     /// The key used for encryption and decryption
-    key: K,
+    key: Key<C>,
     /// The nonce used for encryption and decryption
-    nonce: N,
+    nonce: Iv<C>,
     /// We need `Builder` to be generic over the `StreamCipher`
     _marker: PhantomData<C>,
 }
 
-impl<B, C, K, N> Builder<B, C, K, N>
+impl<B, C> Builder<B, C>
 where
-    B: RwBuilder,
-    C: StreamCipher,
+    C: KeyIvInit,
 {
     /// Create a new cipher builder from a key and a nonce
-    pub const fn new(builder: B, key: K, nonce: N) -> Self {
+    pub const fn new(builder: B, key: Key<C>, nonce: Iv<C>) -> Self {
         Self { builder, key, nonce, _marker: PhantomData }
     }
+
+    /// Create the cipher from the key and the nonce
+    fn create_cipher(&self) -> C {
+        C::new(&self.key, &self.nonce)
+    }
 }
 
 /// The key type for the chacha20 cipher
@@ -51,7 +64,16 @@ pub type ChaCha20Nonce = chacha20::Nonce;
 
 /// The type returned by the `chacha20` function in the `RwBuilder` trait
 #[cfg(feature = "chacha20")]
-pub type ChaCha20Builder<B> = Builder<B, ChaCha20, ChaCha20Key, ChaCha20Nonce>;
+pub type ChaCha20Builder<B> = Builder<B, ChaCha20>;
+
+/// The nonce type for the xchacha20 cipher, which shares the key type of the
+/// chacha20 cipher
+#[cfg(feature = "chacha20")]
+pub type XChaCha20Nonce = chacha20::XNonce;
+
+/// The type returned by the `xchacha20` function in the `RwBuilder` trait
+#[cfg(feature = "chacha20")]
+pub type XChaCha20Builder<B> = Builder<B, XChaCha20>;
 
 /// The key type for the salsa20 cipher
 #[cfg(feature = "salsa20")]
@@ -63,67 +85,265 @@ pub type Salsa20Nonce = salsa20::Nonce;
 
 /// The type returned by the `salsa20` function in the `RwBuilder` trait
 #[cfg(feature = "salsa20")]
-pub type Salsa20Builder<B> = Builder<B, Salsa20, Salsa20Key, Salsa20Nonce>;
+pub type Salsa20Builder<B> = Builder<B, Salsa20>;
 
-/// Recipe for how to create a cipher
-trait CipherFactory<C> {
-    /// Create the cipher from the key and the nonce stored in self
-    fn create_cipher(&self) -> C;
-}
+/// The AES-128 block cipher in counter mode with a big endian 128 bit counter,
+/// which matches e.g. `aes-128-ctr` of OpenSSL
+#[cfg(feature = "aes")]
+pub type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
 
-#[cfg(feature = "chacha20")]
-impl<B> CipherFactory<ChaCha20> for Builder<B, ChaCha20, ChaCha20Key, ChaCha20Nonce>
+/// The key type for the AES-128-CTR cipher
+#[cfg(feature = "aes")]
+pub type Aes128CtrKey = Key<Aes128Ctr>;
+
+/// The type returned by the `aes128_ctr` function in the `RwBuilder` trait
+#[cfg(feature = "aes")]
+pub type Aes128CtrBuilder<B> = Builder<B, Aes128Ctr>;
+
+/// The AES-256 block cipher in counter mode with a big endian 128 bit counter,
+/// which matches e.g. `aes-256-ctr` of OpenSSL
+#[cfg(feature = "aes")]
+pub type Aes256Ctr = ctr::Ctr128BE<aes::Aes256>;
+
+/// The key type for the AES-256-CTR cipher
+#[cfg(feature = "aes")]
+pub type Aes256CtrKey = Key<Aes256Ctr>;
+
+/// The type returned by the `aes256_ctr` function in the `RwBuilder` trait
+#[cfg(feature = "aes")]
+pub type Aes256CtrBuilder<B> = Builder<B, Aes256Ctr>;
+
+/// The initial counter block type shared by the AES-128-CTR and AES-256-CTR
+/// ciphers
+#[cfg(feature = "aes")]
+pub type AesCtrIv = Iv<Aes128Ctr>;
+
+impl<B, C> RwBuilder for Builder<B, C>
 where
     B: RwBuilder,
+    B::Writer: Finish,
+    C: KeyIvInit + StreamCipher,
 {
-    fn create_cipher(&self) -> ChaCha20 {
-        ChaCha20::new(&self.key, &self.nonce)
+    type Reader = Reader<B::Reader, C>;
+    type Writer = Writer<B::Writer, C>;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        let reader = self.builder.reader()?;
+        let cipher = self.create_cipher();
+        Ok(Reader { cipher, reader, header_length: 0 })
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        let writer = self.builder.writer()?;
+        let cipher = self.create_cipher();
+        Ok(Writer { cipher, writer, header_length: 0 })
     }
 }
 
+impl<B, C> SeekableRwBuilder for Builder<B, C>
+where
+    B: SeekableRwBuilder,
+    B::Reader: Seek,
+    B::Writer: Finish + Seek,
+    C: KeyIvInit + StreamCipher + StreamCipherSeek,
+{
+}
+
+#[cfg(feature = "async-tokio")]
+// The futures are `Send` if those of the inner builder are
+#[allow(clippy::future_not_send)]
+impl<B, C> AsyncRwBuilder for Builder<B, C>
+where
+    B: AsyncRwBuilder,
+    C: KeyIvInit + StreamCipher + Unpin,
+{
+    type AsyncReader = Reader<B::AsyncReader, C>;
+    type AsyncWriter = AsyncWriter<B::AsyncWriter, C>;
+
+    async fn async_reader(&self) -> Result<Self::AsyncReader> {
+        let reader = self.builder.async_reader().await?;
+        let cipher = self.create_cipher();
+        Ok(Reader { cipher, reader, header_length: 0 })
+    }
+
+    async fn async_writer(&self) -> Result<Self::AsyncWriter> {
+        let writer = self.builder.async_writer().await?;
+        let cipher = self.create_cipher();
+        Ok(AsyncWriter { cipher, writer, pending: vec![] })
+    }
+}
+
+/// The version of the header written by a `HeaderBuilder`
+const HEADER_VERSION: u8 = 1;
+
+/// Implementors are the ciphers which can be identified by the algorithm byte
+/// in the header written by a `HeaderBuilder`
+pub trait HeaderAlgorithm {
+    /// The algorithm byte, which is unique for every cipher
+    const ALGORITHM: u8;
+}
+
+#[cfg(feature = "chacha20")]
+impl HeaderAlgorithm for ChaCha20 {
+    const ALGORITHM: u8 = 1;
+}
+
+#[cfg(feature = "chacha20")]
+impl HeaderAlgorithm for XChaCha20 {
+    const ALGORITHM: u8 = 2;
+}
+
 #[cfg(feature = "salsa20")]
-impl<B> CipherFactory<Salsa20> for Builder<B, Salsa20, Salsa20Key, Salsa20Nonce>
+impl HeaderAlgorithm for Salsa20 {
+    const ALGORITHM: u8 = 3;
+}
+
+#[cfg(feature = "aes")]
+impl HeaderAlgorithm for Aes128Ctr {
+    const ALGORITHM: u8 = 4;
+}
+
+#[cfg(feature = "aes")]
+impl HeaderAlgorithm for Aes256Ctr {
+    const ALGORITHM: u8 = 5;
+}
+
+/// Type returned by the `stream_cipher_with_header` function on the
+/// `RwBuilder` trait. It is itself an `RwBuilder` so can be chained further.
+///
+/// Every writer starts the stream with a header holding the version, the
+/// algorithm byte and a random nonce, which the readers parse before
+/// decrypting. Seeking the readers and writers is relative to the end of the
+/// header.
+///
+/// A random nonce only makes reusing it for different streams unlikely, the
+/// more so the larger it is. `XChaCha20` has 192 bits and AES-CTR a 128 bit
+/// counter block, while with the 96 bit nonce of `ChaCha20` and the 64 bit
+/// nonce of `Salsa20` a repeat becomes likely after about 2^48 and 2^32
+/// streams with the same key.
+#[derive(Debug)]
+#[allow(clippy::struct_field_names)]
+pub struct HeaderBuilder<B, C>
 where
-    B: RwBuilder,
+    C: KeyIvInit,
 {
-    fn create_cipher(&self) -> Salsa20 {
-        Salsa20::new(&self.key, &self.nonce)
+    /// The inner builder it wraps
+    builder: B,
+    /// The key used for encryption and decryption
+    key: Key<C>,
+    /// We need `HeaderBuilder` to be generic over the `StreamCipher`
+    _marker: PhantomData<C>,
+}
+
+impl<B, C> HeaderBuilder<B, C>
+where
+    C: KeyIvInit,
+{
+    /// Create a new cipher builder from a key
+    pub const fn new(builder: B, key: Key<C>) -> Self {
+        Self { builder, key, _marker: PhantomData }
     }
 }
 
-impl<B, C, K, N> RwBuilder for Builder<B, C, K, N>
+impl<B, C> RwBuilder for HeaderBuilder<B, C>
 where
     B: RwBuilder,
-    C: StreamCipher,
-    Self: CipherFactory<C>,
+    B::Writer: Finish,
+    C: KeyIvInit + StreamCipher + HeaderAlgorithm,
 {
     type Reader = Reader<B::Reader, C>;
     type Writer = Writer<B::Writer, C>;
 
     fn reader(&self) -> Result<Self::Reader> {
-        let reader = self.builder.reader()?;
-        let cipher = self.create_cipher();
-        Ok(Reader { cipher, reader })
+        let mut reader = self.builder.reader()?;
+        let mut header = [0; 2];
+        let mut nonce = Iv::<C>::default();
+        read_header(&mut reader, &mut header)?;
+        let error = match header {
+            [HEADER_VERSION, algorithm] if algorithm == C::ALGORITHM => None,
+            [HEADER_VERSION, algorithm] => Some(HeaderError::UnexpectedAlgorithm(algorithm)),
+            [version, _] => Some(HeaderError::UnsupportedVersion(version)),
+        };
+        if let Some(error) = error {
+            return Err(std::io::Error::from(error).into());
+        }
+        read_header(&mut reader, &mut nonce)?;
+        let header_length = u64::try_from(header.len() + nonce.len())?;
+        Ok(Reader { cipher: C::new(&self.key, &nonce), reader, header_length })
     }
 
     fn writer(&self) -> Result<Self::Writer> {
-        let writer = self.builder.writer()?;
-        let cipher = self.create_cipher();
-        Ok(Writer { cipher, writer })
+        let mut writer = self.builder.writer()?;
+        let mut nonce = Iv::<C>::default();
+        getrandom::getrandom(&mut nonce).map_err(std::io::Error::from)?;
+        let header = [HEADER_VERSION, C::ALGORITHM];
+        writer.write_all(&header)?;
+        writer.write_all(&nonce)?;
+        let header_length = u64::try_from(header.len() + nonce.len())?;
+        Ok(Writer { cipher: C::new(&self.key, &nonce), writer, header_length })
+    }
+}
+
+/// Fill `buf` from the header at the start of the stream
+fn read_header<R>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<()>
+where
+    R: Read,
+{
+    reader.read_exact(buf).map_err(|e| match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => HeaderError::Truncated.into(),
+        _ => e,
+    })
+}
+
+/// The error reported by the reader of a `HeaderBuilder` when the header
+/// can't be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderError {
+    /// The header has a version this crate doesn't know about
+    UnsupportedVersion(u8),
+    /// The stream was encrypted with the cipher identified by this algorithm
+    /// byte instead of the expected one
+    UnexpectedAlgorithm(u8),
+    /// The stream ended inside the header
+    Truncated,
+}
+
+impl Display for HeaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => {
+                write!(f, "the cipher header has the unsupported version {version}")
+            }
+            Self::UnexpectedAlgorithm(algorithm) => {
+                write!(f, "the stream was encrypted with the unexpected algorithm {algorithm}")
+            }
+            Self::Truncated => write!(f, "the cipher header was truncated"),
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {}
+
+impl From<HeaderError> for std::io::Error {
+    fn from(error: HeaderError) -> Self {
+        Self::new(std::io::ErrorKind::InvalidData, error)
     }
 }
 
 /// Generic Reader type for multiple ciphers
 #[derive(Debug)]
+#[allow(clippy::struct_field_names)]
 pub struct Reader<R, C>
 where
-    R: Read,
     C: StreamCipher,
 {
     /// The cipher to use for reading
     cipher: C,
     /// The wrapped reader
     reader: R,
+    /// The length of the header in front of the encrypted bytes, which seek
+    /// positions are relative to
+    header_length: u64,
 }
 
 impl<R, C> Read for Reader<R, C>
@@ -133,24 +353,83 @@ where
 {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         let bytes_read = self.reader.read(buf)?;
-        self.cipher
-            .try_apply_keystream(buf)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        self.cipher.try_apply_keystream(&mut buf[..bytes_read]).map_err(std::io::Error::other)?;
         Ok(bytes_read)
     }
 }
 
+/// Seek in the wrapped stream and move the keystream to the same position,
+/// where positions are relative to the end of the header
+fn seek_cipher<S, C>(
+    stream: &mut S,
+    cipher: &mut C,
+    header_length: u64,
+    pos: SeekFrom,
+) -> std::io::Result<u64>
+where
+    S: Seek,
+    C: StreamCipherSeek,
+{
+    let pos = match pos {
+        SeekFrom::Start(position) => SeekFrom::Start(
+            position
+                .checked_add(header_length)
+                .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::InvalidInput))?,
+        ),
+        pos => pos,
+    };
+    let position = stream.seek(pos)?.checked_sub(header_length).ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "seeked into the cipher header")
+    })?;
+    cipher.try_seek(position).map_err(std::io::Error::other)?;
+    Ok(position)
+}
+
+impl<R, C> Seek for Reader<R, C>
+where
+    R: Read + Seek,
+    C: StreamCipher + StreamCipherSeek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        seek_cipher(&mut self.reader, &mut self.cipher, self.header_length, pos)
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<R, C> tokio::io::AsyncRead for Reader<R, C>
+where
+    R: tokio::io::AsyncRead + Unpin,
+    C: StreamCipher + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        let offset = buf.filled().len();
+        ready!(Pin::new(&mut this.reader).poll_read(cx, buf))?;
+        this.cipher
+            .try_apply_keystream(&mut buf.filled_mut()[offset..])
+            .map_err(std::io::Error::other)?;
+        Poll::Ready(Ok(()))
+    }
+}
+
 /// Generic Writer type for multiple ciphers
 #[derive(Debug)]
+#[allow(clippy::struct_field_names)]
 pub struct Writer<W, C>
 where
-    W: Write,
     C: StreamCipher,
 {
     /// The cipher to use for writing
     cipher: C,
     /// The wrapped writer
     writer: W,
+    /// The length of the header in front of the encrypted bytes, which seek
+    /// positions are relative to
+    header_length: u64,
 }
 
 impl<W, C> Write for Writer<W, C>
@@ -158,15 +437,106 @@ where
     W: Write,
     C: StreamCipher,
 {
+    /// The keystream is applied to all of `buf` at once, so all of it is
+    /// written before returning. Otherwise the cipher would run ahead of the
+    /// bytes that were actually written.
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         let mut buffer = buf.to_owned();
-        self.cipher
-            .try_apply_keystream(buffer.as_mut_slice())
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-        self.writer.write(buffer.as_slice())
+        self.cipher.try_apply_keystream(buffer.as_mut_slice()).map_err(std::io::Error::other)?;
+        self.writer.write_all(buffer.as_slice())?;
+        Ok(buf.len())
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
         self.writer.flush()
     }
 }
+
+impl<W, C> Seek for Writer<W, C>
+where
+    W: Write + Seek,
+    C: StreamCipher + StreamCipherSeek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        seek_cipher(&mut self.writer, &mut self.cipher, self.header_length, pos)
+    }
+}
+
+impl<W, C> Finish for Writer<W, C>
+where
+    W: Write + Finish,
+    C: StreamCipher,
+{
+    fn finish(self) -> Result<()> {
+        self.writer.finish()
+    }
+}
+
+/// Generic async Writer type for multiple ciphers
+/// The encrypted bytes are kept until the wrapped writer accepted them, since
+/// the keystream can't be applied to the same bytes twice.
+#[cfg(feature = "async-tokio")]
+#[derive(Debug)]
+pub struct AsyncWriter<W, C>
+where
+    C: StreamCipher,
+{
+    /// The cipher to use for writing
+    cipher: C,
+    /// The wrapped writer
+    writer: W,
+    /// The encrypted bytes that weren't written to the wrapped writer yet
+    pending: Vec<u8>,
+}
+
+#[cfg(feature = "async-tokio")]
+impl<W, C> AsyncWriter<W, C>
+where
+    W: tokio::io::AsyncWrite + Unpin,
+    C: StreamCipher,
+{
+    /// Write the pending encrypted bytes to the wrapped writer
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        while !self.pending.is_empty() {
+            let bytes_written = ready!(Pin::new(&mut self.writer).poll_write(cx, &self.pending))?;
+            if bytes_written == 0 {
+                return Poll::Ready(Err(std::io::ErrorKind::WriteZero.into()));
+            }
+            let _ = self.pending.drain(..bytes_written);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<W, C> tokio::io::AsyncWrite for AsyncWriter<W, C>
+where
+    W: tokio::io::AsyncWrite + Unpin,
+    C: StreamCipher + Unpin,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_pending(cx))?;
+        this.pending.extend_from_slice(buf);
+        this.cipher.try_apply_keystream(&mut this.pending).map_err(std::io::Error::other)?;
+        // Errors and pending writes are reported by the next call
+        let _ = this.poll_pending(cx);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_pending(cx))?;
+        Pin::new(&mut this.writer).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_pending(cx))?;
+        Pin::new(&mut this.writer).poll_shutdown(cx)
+    }
+}
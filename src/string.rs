@@ -5,7 +5,7 @@ use std::{
 
 use anyhow::Result;
 
-use crate::RwBuilder;
+use crate::{Finish, RwBuilder};
 
 /// Type returned by the `string` function on the `RwBuilder` trait.
 /// It is itself not an `RwBuilder` so can't be chained further.
@@ -68,8 +68,7 @@ where
 {
     fn write_string(&self, text: &str) -> Result<()> {
         let mut writer = self.builder.writer()?;
-        let byte_count = writer.write(text.as_bytes())?;
-        assert_eq!(byte_count, text.len());
-        Ok(writer.flush()?)
+        writer.write_all(text.as_bytes())?;
+        writer.finish()
     }
 }
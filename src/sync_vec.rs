@@ -0,0 +1,180 @@
+use std::{
+    cmp::min,
+    io::{Read, Seek, SeekFrom, Write},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+};
+
+use anyhow::Result;
+
+use crate::{Finish, RwBuilder, SeekableRwBuilder, seek_position};
+
+/// The state shared between the builder, readers and writers
+#[derive(Debug, Default)]
+struct Shared {
+    /// The buffer and the writers that are open
+    state: Mutex<State>,
+    /// Signalled whenever data is written or a writer is closed
+    changed: Condvar,
+}
+
+/// The part of the shared state protected by the mutex
+#[derive(Debug, Default)]
+struct State {
+    /// The bytes written so far
+    buffer: Vec<u8>,
+    /// The number of writers that are open
+    writers: usize,
+    /// Whether the last open writer was finished or dropped since the last
+    /// one was built
+    closed: bool,
+}
+
+impl Shared {
+    /// Lock the state, turning a poisoned mutex into an I/O error
+    fn lock(&self) -> std::io::Result<MutexGuard<'_, State>> {
+        self.state.lock().map_err(|_| std::io::Error::other("the buffer mutex was poisoned"))
+    }
+}
+
+/// Type for building readers and writers on top of a `Vec` in memory which
+/// can be shared between threads.
+///
+/// Clones of the builder share the same buffer. It is itself an `RwBuilder`,
+/// but can't be created through one. This is why we call it a source.
+#[derive(Debug, Default, Clone)]
+pub struct Builder {
+    /// The buffer shared between the builder, readers and writers
+    shared: Arc<Shared>,
+    /// Whether readers wait for more data until all writers are closed
+    pipe: bool,
+}
+
+impl Builder {
+    /// Make the readers behave like the reading end of a pipe: instead of
+    /// reporting the end of the buffer they wait for more data, until all
+    /// writers are finished or dropped
+    #[must_use]
+    pub fn pipe(self) -> Self {
+        Self { pipe: true, ..self }
+    }
+}
+
+impl RwBuilder for Builder {
+    type Reader = Reader;
+    type Writer = Writer;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        Ok(Reader { shared: Arc::clone(&self.shared), pipe: self.pipe, bytes_read: 0 })
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        let mut state = self.shared.lock()?;
+        state.writers += 1;
+        state.closed = false;
+        let position = state.buffer.len();
+        drop(state);
+        Ok(Writer { shared: Arc::clone(&self.shared), position })
+    }
+}
+
+impl SeekableRwBuilder for Builder {}
+
+/// Reading from a `Vec` shared between threads
+#[derive(Debug)]
+pub struct Reader {
+    /// The buffer shared between the builder, readers and writers
+    shared: Arc<Shared>,
+    /// Whether the reader waits for more data until all writers are closed
+    pipe: bool,
+    /// The read position in the buffer
+    bytes_read: usize,
+}
+
+impl Read for Reader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut state = self.shared.lock()?;
+        while self.pipe && !buf.is_empty() && !state.closed && state.buffer.len() <= self.bytes_read
+        {
+            state = self
+                .shared
+                .changed
+                .wait(state)
+                .map_err(|_| std::io::Error::other("the buffer mutex was poisoned"))?;
+        }
+        let start = min(self.bytes_read, state.buffer.len());
+        let byte_count = min(state.buffer.len() - start, buf.len());
+        buf[..byte_count].copy_from_slice(&state.buffer[start..start + byte_count]);
+        drop(state);
+        self.bytes_read = start + byte_count;
+        Ok(byte_count)
+    }
+}
+
+impl Seek for Reader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let len = self.shared.lock()?.buffer.len();
+        self.bytes_read = seek_position(self.bytes_read, len, pos)?;
+        u64::try_from(self.bytes_read).map_err(std::io::Error::other)
+    }
+}
+
+/// Writing to a `Vec` shared between threads
+/// Waiting readers are woken up on every write and when the last open writer
+/// is finished or dropped.
+#[derive(Debug)]
+pub struct Writer {
+    /// The buffer shared between the builder, readers and writers
+    shared: Arc<Shared>,
+    /// The write position in the buffer, which starts at the end of the buffer
+    position: usize,
+}
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut state = self.shared.lock()?;
+        let end = self.position.checked_add(buf.len()).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "writing past the maximum size of a vec",
+            )
+        })?;
+        if state.buffer.len() < end {
+            state.buffer.resize(end, 0);
+        }
+        state.buffer[self.position..end].copy_from_slice(buf);
+        drop(state);
+        self.position = end;
+        self.shared.changed.notify_all();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for Writer {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let len = self.shared.lock()?.buffer.len();
+        self.position = seek_position(self.position, len, pos)?;
+        u64::try_from(self.position).map_err(std::io::Error::other)
+    }
+}
+
+impl Finish for Writer {
+    fn finish(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        // A poisoned mutex can't be reported while dropping, but the readers
+        // will report it themselves
+        if let Ok(mut state) = self.shared.lock() {
+            state.writers -= 1;
+            state.closed = state.writers == 0;
+        }
+        self.shared.changed.notify_all();
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    io::ErrorKind,
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use anyhow::Result;
+use socket2::SockRef;
+
+use crate::{RwBuilder, tcp_stream::ConnectedBuilder};
+
+/// Type for building readers and writers on top of the connections accepted
+/// by a listening TCP socket.
+///
+/// Every reader and writer accepts a new connection. It is itself an
+/// `RwBuilder`, but can't be created through one. This is why we call it a
+/// source.
+#[derive(Debug)]
+pub struct Builder {
+    /// The socket listening for connections
+    listener: TcpListener,
+    /// How long to wait for a connection before giving up, forever if `None`
+    timeout: Option<Duration>,
+}
+
+impl Builder {
+    /// Create a builder listening on the given address
+    /// # Errors
+    /// Propagates the error of failing to bind the socket, e.g. because the
+    /// address is already in use.
+    pub fn bind<A>(addr: A) -> Result<Self>
+    where
+        A: ToSocketAddrs,
+    {
+        Ok(Self::new(TcpListener::bind(addr)?))
+    }
+
+    /// Create a builder on top of an already bound listener
+    #[must_use]
+    pub const fn new(listener: TcpListener) -> Self {
+        Self { listener, timeout: None }
+    }
+
+    /// Fail with `ErrorKind::TimedOut` when no connection arrives in time.
+    ///
+    /// The timeout is set as the receive timeout of the listening socket,
+    /// which Linux and the BSDs apply to accepting connections but Windows
+    /// ignores.
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout: Some(timeout), ..self }
+    }
+
+    /// The address the listener is bound to, e.g. to find the port picked by
+    /// the operating system when binding to port 0
+    /// # Errors
+    /// Propagates the error of failing to query the socket.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Accept the next connection and return a builder whose reader and writer
+    /// share it.
+    /// # Errors
+    /// Propagates the error of failing to accept a connection, or of the
+    /// timeout expiring.
+    pub fn accept(&self) -> Result<ConnectedBuilder> {
+        Ok(ConnectedBuilder::new(self.accept_stream()?))
+    }
+
+    /// Accept the next connection, waiting at most for the timeout if there
+    /// is one
+    fn accept_stream(&self) -> std::io::Result<TcpStream> {
+        let Some(timeout) = self.timeout else {
+            return self.listener.accept().map(|(stream, _)| stream);
+        };
+        SockRef::from(&self.listener).set_read_timeout(Some(timeout))?;
+        match self.listener.accept() {
+            // The accepted stream inherits the receive timeout on some systems
+            Ok((stream, _)) => stream.set_read_timeout(None).map(|()| stream),
+            Err(e) if e.kind() == ErrorKind::WouldBlock => Err(std::io::Error::new(
+                ErrorKind::TimedOut,
+                "no connection was accepted before the timeout",
+            )),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl RwBuilder for Builder {
+    type Reader = TcpStream;
+    type Writer = TcpStream;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        Ok(self.accept_stream()?)
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        Ok(self.accept_stream()?)
+    }
+}
@@ -1,8 +1,14 @@
-use std::net::{TcpStream, ToSocketAddrs};
+use std::{
+    io::Write,
+    net::{Shutdown, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
 
 use anyhow::Result;
 
-use crate::RwBuilder;
+#[cfg(feature = "async-tokio")]
+use crate::AsyncRwBuilder;
+use crate::{Finish, RwBuilder};
 
 /// Type for building readers and writers on top of a connected TCP socket.
 /// It is itself an `RwBuilder`, but can't be created through one.
@@ -14,6 +20,15 @@ where
 {
     /// The address to connect to
     addr: A,
+    /// How long to wait for the connection to be established, forever if
+    /// `None`
+    connect_timeout: Option<Duration>,
+    /// How long a read may block, forever if `None`
+    read_timeout: Option<Duration>,
+    /// How long a write may block, forever if `None`
+    write_timeout: Option<Duration>,
+    /// Whether Nagle's algorithm is disabled on the stream
+    nodelay: bool,
 }
 
 impl<A> Builder<A>
@@ -22,7 +37,78 @@ where
 {
     /// Factory function to create a builder holding on to a socket address
     pub const fn new(addr: A) -> Self {
-        Self { addr }
+        Self {
+            addr,
+            connect_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+            nodelay: false,
+        }
+    }
+
+    /// Fail with `ErrorKind::TimedOut` when the connection can't be
+    /// established in time
+    #[must_use]
+    pub fn connect_timeout(self, timeout: Duration) -> Self {
+        Self { connect_timeout: Some(timeout), ..self }
+    }
+
+    /// Fail with `ErrorKind::WouldBlock` or `ErrorKind::TimedOut` when a read
+    /// blocks for too long. Not supported by the async streams.
+    #[must_use]
+    pub fn read_timeout(self, timeout: Duration) -> Self {
+        Self { read_timeout: Some(timeout), ..self }
+    }
+
+    /// Fail with `ErrorKind::WouldBlock` or `ErrorKind::TimedOut` when a write
+    /// blocks for too long. Not supported by the async streams.
+    #[must_use]
+    pub fn write_timeout(self, timeout: Duration) -> Self {
+        Self { write_timeout: Some(timeout), ..self }
+    }
+
+    /// Disable Nagle's algorithm so small writes are sent immediately
+    #[must_use]
+    pub fn nodelay(self) -> Self {
+        Self { nodelay: true, ..self }
+    }
+
+    /// Connect once and return a builder whose reader and writer share the
+    /// same stream, like `ProcessBuilder::spawn` does for a child process.
+    /// # Errors
+    /// Propagates the error of failing to connect to the address.
+    pub fn connect(&self) -> Result<ConnectedBuilder> {
+        Ok(ConnectedBuilder::new(self.open()?))
+    }
+
+    /// Connect to the address and configure the stream with the options
+    fn open(&self) -> Result<TcpStream> {
+        let stream = match self.connect_timeout {
+            Some(timeout) => self.connect_with_timeout(timeout)?,
+            None => TcpStream::connect(&self.addr)?,
+        };
+        stream.set_read_timeout(self.read_timeout)?;
+        stream.set_write_timeout(self.write_timeout)?;
+        stream.set_nodelay(self.nodelay)?;
+        Ok(stream)
+    }
+
+    /// Try every address the socket address resolves to in turn, returning
+    /// the last error if none of them could be connected to in time
+    fn connect_with_timeout(&self, timeout: Duration) -> std::io::Result<TcpStream> {
+        let mut last_error = None;
+        for addr in self.addr.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, timeout) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "could not resolve to any addresses",
+            )
+        }))
     }
 }
 
@@ -34,12 +120,100 @@ where
     type Writer = TcpStream;
 
     fn reader(&self) -> Result<Self::Reader> {
-        let stream = TcpStream::connect(&self.addr)?;
-        Ok(stream)
+        self.open()
     }
 
     fn writer(&self) -> Result<Self::Writer> {
-        let stream = TcpStream::connect(&self.addr)?;
+        self.open()
+    }
+}
+
+/// Type for building readers and writers on top of a single connected TCP
+/// socket.
+///
+/// The reader and the writer are clones of the same stream, so a request
+/// written by the writer is answered on the reader. It is itself an
+/// `RwBuilder`, so it can be chained further. It is also a source.
+#[derive(Debug)]
+pub struct ConnectedBuilder {
+    /// The connected stream that is cloned for every reader and writer
+    stream: TcpStream,
+}
+
+impl ConnectedBuilder {
+    /// Create a builder sharing an already connected stream
+    #[must_use]
+    pub const fn new(stream: TcpStream) -> Self {
+        Self { stream }
+    }
+}
+
+impl RwBuilder for ConnectedBuilder {
+    type Reader = TcpStream;
+    type Writer = TcpStream;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        Ok(self.stream.try_clone()?)
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        Ok(self.stream.try_clone()?)
+    }
+}
+
+impl Finish for TcpStream {
+    /// Flushes the stream and shuts down its writing half so the peer reads
+    /// the end of the stream.
+    fn finish(mut self) -> Result<()> {
+        self.flush()?;
+        Ok(self.shutdown(Shutdown::Write)?)
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<A> Builder<A>
+where
+    A: ToSocketAddrs + tokio::net::ToSocketAddrs + Sync,
+{
+    /// Connect to the address asynchronously and configure the stream with
+    /// the options. Tokio streams have no read and write timeouts, so setting
+    /// them fails with `ErrorKind::Unsupported` instead of silently ignoring
+    /// them.
+    async fn open_async(&self) -> Result<tokio::net::TcpStream> {
+        if self.read_timeout.is_some() || self.write_timeout.is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "read and write timeouts aren't supported on async streams",
+            )
+            .into());
+        }
+        let connect = tokio::net::TcpStream::connect(&self.addr);
+        let stream = match self.connect_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, connect)
+                .await
+                .map_err(|_| std::io::Error::from(std::io::ErrorKind::TimedOut))??,
+            None => connect.await?,
+        };
+        stream.set_nodelay(self.nodelay)?;
         Ok(stream)
     }
 }
+
+/// The connect timeout and `nodelay` apply to the async streams as well, the
+/// read and write timeouts can't be used with them.
+#[cfg(feature = "async-tokio")]
+impl<A> AsyncRwBuilder for Builder<A>
+where
+    A: ToSocketAddrs + tokio::net::ToSocketAddrs + Sync,
+{
+    type AsyncReader = tokio::net::TcpStream;
+    type AsyncWriter = tokio::net::TcpStream;
+
+    async fn async_reader(&self) -> Result<Self::AsyncReader> {
+        self.open_async().await
+    }
+
+    async fn async_writer(&self) -> Result<Self::AsyncWriter> {
+        self.open_async().await
+    }
+}
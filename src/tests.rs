@@ -3,7 +3,67 @@ use std::{env::temp_dir, process::Command};
 #[cfg(feature = "flate2")]
 use ::flate2::Compression;
 
-use super::*;
+#[cfg(feature = "chacha20")]
+#[test]
+fn chacha20_short_writes() {
+    use std::io::{ErrorKind, Write};
+
+    /// Builds writers which write at most three bytes at a time and are
+    /// interrupted every other call
+    struct ShortBuilder(VecBuilder);
+
+    struct ShortWriter(crate::vec::Writer, bool);
+
+    impl RwBuilder for ShortBuilder {
+        type Reader = crate::vec::Reader;
+        type Writer = ShortWriter;
+
+        fn reader(&self) -> Result<Self::Reader> {
+            self.0.reader()
+        }
+
+        fn writer(&self) -> Result<Self::Writer> {
+            Ok(ShortWriter(self.0.writer()?, false))
+        }
+    }
+
+    impl Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.1 = !self.1;
+            if self.1 {
+                return Err(ErrorKind::Interrupted.into());
+            }
+            self.0.write(&buf[..buf.len().min(3)])
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.0.flush()
+        }
+    }
+
+    impl Finish for ShortWriter {
+        fn finish(self) -> Result<()> {
+            self.0.finish()
+        }
+    }
+
+    let key = [0x42; 32];
+    let nonce = [0x24; 12];
+    test_string(ShortBuilder(VecBuilder::default()).chacha20(key.into(), nonce.into()));
+}
+
+#[cfg(feature = "chacha20poly1305")]
+use super::AeadError;
+#[cfg(feature = "mmap")]
+use super::MmapFileBuilder;
+#[cfg(any(feature = "bincode", feature = "json"))]
+use super::SerDe;
+use super::{
+    FileBuilder, Finish, ProcessBuilder, Result, RwBuilder, SeekableRwBuilder, StderrBuilder,
+    StdioBuilder, SyncVecBuilder, TcpListenerBuilder, TcpStreamBuilder, VecBuilder,
+};
+#[cfg(unix)]
+use super::{ProcessError, UnixDatagramBuilder, UnixListenerBuilder, UnixStreamBuilder};
 use crate::string::AdhocWriter;
 
 fn write_and_read_string<B>(builder: B, input: &str) -> Result<String>
@@ -15,7 +75,6 @@ where
     Ok(string.to_string())
 }
 
-#[cfg(any(feature = "flate2", feature = "chacha20", feature = "salsa20"))]
 fn test_string<B>(builder: B)
 where
     B: RwBuilder,
@@ -25,6 +84,11 @@ where
     assert_eq!(actual, text);
 }
 
+#[test]
+fn vec() {
+    test_string(VecBuilder::default());
+}
+
 #[cfg(feature = "flate2")]
 #[test]
 fn compression() {
@@ -33,6 +97,147 @@ fn compression() {
     test_string(VecBuilder::default().deflate(Compression::fast()));
 }
 
+#[cfg(feature = "brotli")]
+#[test]
+fn brotli() {
+    use std::io::{Read, Write};
+    test_string(VecBuilder::default().brotli(11, 22));
+    test_string(VecBuilder::default().brotli(0, 10).buffered());
+    let buffer = SyncVecBuilder::default();
+    let mut writer = buffer.clone().brotli(5, 18).writer().expect("Writer couldn't be created.");
+    writer.write_all(b"precompressed asset").expect("Couldn't write data.");
+    writer.finish().expect("Writer couldn't be finished.");
+    let mut decompressed = vec![];
+    let reader = buffer.reader().expect("Reader couldn't be created.");
+    let _ = ::brotli::Decompressor::new(reader, 4096)
+        .read_to_end(&mut decompressed)
+        .expect("Couldn't decompress data.");
+    assert_eq!(decompressed, b"precompressed asset");
+}
+
+/// Write two compressed streams after each other, check that the compressed
+/// bytes start with `magic` and that both streams are read back
+#[cfg(any(feature = "bzip2", feature = "xz"))]
+fn test_concatenated_streams<B, F>(compress: F, magic: &[u8])
+where
+    B: RwBuilder,
+    F: Fn(SyncVecBuilder) -> B,
+{
+    use std::io::{Read, Write};
+    let buffer = SyncVecBuilder::default();
+    let builder = compress(buffer.clone());
+    for text in ["first stream, ", "second stream"] {
+        let mut writer = builder.writer().expect("Writer couldn't be created.");
+        writer.write_all(text.as_bytes()).expect("Couldn't write data.");
+        writer.finish().expect("Writer couldn't be finished.");
+    }
+    let mut compressed = vec![];
+    let mut reader = buffer.reader().expect("Reader couldn't be created.");
+    let _ = reader.read_to_end(&mut compressed).expect("Couldn't read data.");
+    assert!(compressed.starts_with(magic));
+    let mut text = String::new();
+    let mut reader = builder.reader().expect("Reader couldn't be created.");
+    let _ = reader.read_to_string(&mut text).expect("Couldn't read data.");
+    assert_eq!(text, "first stream, second stream");
+}
+
+#[cfg(feature = "xz")]
+#[test]
+fn xz() {
+    test_string(VecBuilder::default().xz(6));
+    test_concatenated_streams(|buffer| buffer.xz(0), &[0xfd, b'7', b'z', b'X', b'Z', 0x00]);
+    let error = VecBuilder::default().xz(10).writer().err();
+    let kind =
+        error.as_ref().and_then(|e| e.downcast_ref::<std::io::Error>()).map(std::io::Error::kind);
+    assert_eq!(kind, Some(std::io::ErrorKind::InvalidInput));
+}
+
+#[cfg(feature = "bzip2")]
+#[test]
+fn bzip2() {
+    test_string(VecBuilder::default().bzip2(9));
+    test_concatenated_streams(|buffer| buffer.bzip2(1), b"BZh1");
+    for level in [0, 10] {
+        let error = VecBuilder::default().bzip2(level).writer().err();
+        let kind = error
+            .as_ref()
+            .and_then(|e| e.downcast_ref::<std::io::Error>())
+            .map(std::io::Error::kind);
+        assert_eq!(kind, Some(std::io::ErrorKind::InvalidInput));
+    }
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn lz4() {
+    use std::io::{Read, Write};
+    test_string(VecBuilder::default().lz4(0));
+    test_string(VecBuilder::default().lz4(9).content_checksum(false).block_independence(false));
+    let frame_flags = |content_checksum: bool, block_independence: bool| {
+        let buffer = SyncVecBuilder::default();
+        let builder = buffer
+            .clone()
+            .lz4(1)
+            .content_checksum(content_checksum)
+            .block_independence(block_independence);
+        let mut writer = builder.writer().expect("Writer couldn't be created.");
+        writer.write_all(b"hot cache entry").expect("Couldn't write data.");
+        writer.finish().expect("Writer couldn't be finished.");
+        let mut frame = vec![];
+        let mut reader = buffer.reader().expect("Reader couldn't be created.");
+        let _ = reader.read_to_end(&mut frame).expect("Couldn't read frame.");
+        assert_eq!(frame[..4], [0x04, 0x22, 0x4d, 0x18]);
+        frame[4]
+    };
+    assert_eq!(frame_flags(true, true) & 0b0010_0100, 0b0010_0100);
+    assert_eq!(frame_flags(false, false) & 0b0010_0100, 0);
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd() {
+    test_string(VecBuilder::default().zstd(3));
+    test_string(VecBuilder::default().zstd(19).long_distance_matching().multithread(2));
+    let dictionary = b"This text is written from a String and read back".to_vec();
+    test_string(VecBuilder::default().zstd(3).dictionary(dictionary));
+}
+
+#[cfg(feature = "base64")]
+#[test]
+fn base64() {
+    use super::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+    test_string(VecBuilder::default().base64(STANDARD));
+    test_string(VecBuilder::default().base64(URL_SAFE_NO_PAD).buffered());
+    let builder = VecBuilder::from_slice(b"SGVsbG8g\nd29ybGQu\n").base64(STANDARD).string();
+    assert_eq!(builder.to_string(), "Hello world.");
+}
+
+#[cfg(all(feature = "base64", feature = "chacha20", feature = "bincode"))]
+#[test]
+fn base64_token() {
+    use super::general_purpose::URL_SAFE;
+    let builder = VecBuilder::default();
+    let token = builder.base64(URL_SAFE).chacha20([0x42; 32].into(), [0x24; 12].into()).bincode();
+    token.save(&(42u32, String::from("secret"))).expect("Couldn't save token.");
+    let loaded: (u32, String) = token.load().expect("Couldn't load token.");
+    assert_eq!(loaded, (42, String::from("secret")));
+}
+
+#[cfg(feature = "hex")]
+#[test]
+fn hex() {
+    test_string(VecBuilder::default().hex());
+    assert_eq!(VecBuilder::from_slice(b"48 65 6C 6c 6F").hex().string().to_string(), "Hello");
+}
+
+#[cfg(feature = "base32")]
+#[test]
+fn base32() {
+    test_string(VecBuilder::default().base32());
+    let builder = VecBuilder::from_slice(b"JBSWY3DPEE======").base32().string();
+    assert_eq!(builder.to_string(), "Hello!");
+}
+
 #[cfg(feature = "flate2")]
 #[test]
 fn crc() {
@@ -55,6 +260,59 @@ fn crc() {
     }
 }
 
+#[cfg(feature = "digest")]
+#[test]
+fn digest() {
+    use std::io::{Read, Write};
+    let data = b"abc";
+    let sha256 = VecBuilder::default().digest::<sha2::Sha256>();
+    let mut writer = sha256.writer().expect("Writer couldn't be created.");
+    writer.write_all(data).expect("Couldn't write data.");
+    assert_eq!(writer.digest().amount(), 3);
+    assert_eq!(
+        format!("{:x}", writer.digest().sum()),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    let blake3 = VecBuilder::from_slice(data).digest::<blake3::Hasher>();
+    let mut reader = blake3.reader().expect("Reader couldn't be created.");
+    let mut buffer = vec![];
+    let _ = reader.read_to_end(&mut buffer).expect("Couldn't read into buffer.");
+    assert_eq!(buffer, data);
+    assert_eq!(reader.digest().amount(), 3);
+    assert_eq!(reader.digest().sum().as_slice(), blake3::hash(data).as_bytes());
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn finish() {
+    use std::io::{Read, Write};
+    let data = [1, 2, 3, 4, 5];
+    let builder = VecBuilder::default().buffered().gz(Compression::fast()).crc();
+    let mut writer = builder.writer().expect("Writer couldn't be created.");
+    writer.write_all(&data).expect("Couldn't write data.");
+    writer.finish().expect("Couldn't finish writer.");
+    let mut buffer = vec![];
+    let _ = builder.reader().expect("Reader couldn't be created.").read_to_end(&mut buffer);
+    assert_eq!(buffer, data);
+}
+
+#[cfg(all(feature = "bincode", feature = "flate2", target_os = "linux"))]
+#[test]
+fn finish_error() {
+    let builder = FileBuilder::new("/dev/full".into()).gz(Compression::fast()).bincode();
+    assert!(builder.save(&"This string doesn't fit on the device.").is_err());
+}
+
+#[cfg(all(feature = "brotli", target_os = "linux"))]
+#[test]
+fn brotli_finish_error() {
+    use std::io::Write;
+    let builder = FileBuilder::new("/dev/full".into()).brotli(11, 22);
+    let mut writer = builder.writer().expect("Writer couldn't be created.");
+    writer.write_all(b"This string doesn't fit on the device.").expect("Couldn't write data.");
+    assert!(writer.finish().is_err());
+}
+
 #[cfg(feature = "chacha20")]
 #[test]
 fn chacha20() {
@@ -63,6 +321,44 @@ fn chacha20() {
     test_string(VecBuilder::default().chacha20(key.into(), nonce.into()));
 }
 
+#[cfg(feature = "chacha20poly1305")]
+#[test]
+fn aead_chacha20poly1305() {
+    use std::io::Read;
+    let key = [0x42; 32];
+    test_string(VecBuilder::default().aead_chacha20poly1305(key.into()));
+    let path = temp_dir().join("test_aead.bin");
+    let builder = FileBuilder::new(path.clone()).aead_chacha20poly1305(key.into());
+    let data: Vec<u8> = (0..200_000_u32).flat_map(u32::to_le_bytes).collect();
+    let read_back = |bytes: &[u8]| -> Result<Vec<u8>> {
+        std::fs::write(&path, bytes)?;
+        let mut buffer = vec![];
+        let _ = builder.reader()?.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    };
+    let read_error = |bytes: &[u8]| -> Option<AeadError> {
+        let error = read_back(bytes).expect_err("Reading should fail.");
+        error.downcast_ref::<std::io::Error>()?.get_ref()?.downcast_ref::<AeadError>().copied()
+    };
+    let mut writer = builder.writer().expect("Writer couldn't be created.");
+    std::io::Write::write_all(&mut writer, &data).expect("Couldn't write data.");
+    writer.finish().expect("Couldn't finish writer.");
+    let mut bytes = std::fs::read(&path).expect("File couldn't be read.");
+    assert_eq!(read_back(&bytes).expect("Couldn't read back data."), data);
+    assert_eq!(read_error(&bytes[..bytes.len() - 1]), Some(AeadError::Tampered));
+    assert_eq!(read_error(&bytes[..7 + 64 * 1024 + 16]), Some(AeadError::Truncated));
+    bytes[1000] ^= 1;
+    assert_eq!(read_error(&bytes), Some(AeadError::Tampered));
+    let mut reader = builder.reader().expect("Reader couldn't be created.");
+    let mut buffer = [0; 16];
+    for _ in 0..2 {
+        let error = reader.read(&mut buffer).expect_err("Reading should keep failing.");
+        let error = error.get_ref().and_then(|e| e.downcast_ref::<AeadError>()).copied();
+        assert_eq!(error, Some(AeadError::Tampered));
+    }
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+}
+
 #[cfg(feature = "salsa20")]
 #[test]
 fn salsa20() {
@@ -71,6 +367,114 @@ fn salsa20() {
     test_string(VecBuilder::default().salsa20(key.into(), nonce.into()));
 }
 
+#[cfg(feature = "chacha20")]
+#[test]
+fn xchacha20() {
+    let key = [0x42; 32];
+    let nonce = [0x24; 24];
+    test_string(VecBuilder::default().xchacha20(key.into(), nonce.into()));
+}
+
+#[cfg(feature = "chacha20")]
+#[test]
+fn stream_cipher_with_header() {
+    use std::io::Read;
+
+    use super::{ChaCha20, HeaderError, XChaCha20};
+    let key = [0x42; 32];
+    test_string(VecBuilder::default().stream_cipher_with_header::<XChaCha20>(key.into()));
+    let encrypt = |text: &str| {
+        let buffer = SyncVecBuilder::default();
+        let builder = buffer.clone().stream_cipher_with_header::<XChaCha20>(key.into());
+        builder.string().write_string(text).expect("String couldn't be written");
+        let mut bytes = vec![];
+        let mut reader = buffer.reader().expect("Reader couldn't be created.");
+        let _ = reader.read_to_end(&mut bytes).expect("Couldn't read into buffer.");
+        bytes
+    };
+    let first = encrypt("same plaintext");
+    let second = encrypt("same plaintext");
+    assert_eq!(first.len(), 2 + 24 + 14);
+    assert_eq!(first[..2], [1, 2]);
+    assert_ne!(first, second);
+    let read_error = |bytes: &[u8]| -> Option<HeaderError> {
+        let builder =
+            VecBuilder::from_slice(bytes).stream_cipher_with_header::<ChaCha20>(key.into());
+        let error = builder.reader().err()?;
+        error.downcast_ref::<std::io::Error>()?.get_ref()?.downcast_ref::<HeaderError>().copied()
+    };
+    assert_eq!(read_error(&first), Some(HeaderError::UnexpectedAlgorithm(2)));
+    assert_eq!(read_error(&[2, 1]), Some(HeaderError::UnsupportedVersion(2)));
+    assert_eq!(read_error(&[1, 1, 0, 0]), Some(HeaderError::Truncated));
+}
+
+#[cfg(feature = "chacha20")]
+#[test]
+fn seek_stream_cipher_with_header() {
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    use super::XChaCha20;
+    let path = temp_dir().join("test_seek_stream_cipher_with_header.bin");
+    let builder =
+        FileBuilder::new(path.clone()).stream_cipher_with_header::<XChaCha20>([0x42; 32].into());
+    let result = (|| -> Result<(u64, String, String)> {
+        let mut writer = builder.writer()?;
+        writer.write_all(b"0123456789")?;
+        let position = writer.seek(SeekFrom::Start(2))?;
+        writer.write_all(b"ab")?;
+        writer.finish()?;
+        let mut reader = builder.reader()?;
+        let mut all = String::new();
+        let _ = reader.read_to_string(&mut all)?;
+        let _ = reader.seek(SeekFrom::Start(5))?;
+        let mut tail = String::new();
+        let _ = reader.read_to_string(&mut tail)?;
+        Ok((position, all, tail))
+    })();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    let (position, all, tail) = result.expect("Couldn't seek.");
+    assert_eq!(position, 2);
+    assert_eq!(all, "01ab456789");
+    assert_eq!(tail, "56789");
+}
+
+#[cfg(feature = "aes")]
+#[test]
+fn aes_ctr() {
+    use std::io::Read;
+    // The first block of the CTR examples in NIST SP 800-38A
+    let plaintext = b"\x6b\xc1\xbe\xe2\x2e\x40\x9f\x96\xe9\x3d\x7e\x11\x73\x93\x17\x2a";
+    let iv = *b"\xf0\xf1\xf2\xf3\xf4\xf5\xf6\xf7\xf8\xf9\xfa\xfb\xfc\xfd\xfe\xff";
+    let key_128 = *b"\x2b\x7e\x15\x16\x28\xae\xd2\xa6\xab\xf7\x15\x88\x09\xcf\x4f\x3c";
+    let ciphertext_128 = b"\x87\x4d\x61\x91\xb6\x20\xe3\x26\x1b\xef\x68\x64\x99\x0d\xb6\xce";
+    let key_256 = *b"\x60\x3d\xeb\x10\x15\xca\x71\xbe\x2b\x73\xae\xf0\x85\x7d\x77\x81\
+                    \x1f\x35\x2c\x07\x3b\x61\x08\xd7\x2d\x98\x10\xa3\x09\x14\xdf\xf4";
+    let ciphertext_256 = b"\x60\x1e\xc3\x13\x77\x57\x89\xa5\xb7\xa7\xf5\x04\xbb\xf3\xd2\x28";
+    let decrypt = |mut reader: Box<dyn Read>| {
+        let mut buffer = vec![];
+        let _ = reader.read_to_end(&mut buffer).expect("Couldn't read into buffer.");
+        buffer
+    };
+    let aes_128 = VecBuilder::from_slice(ciphertext_128).aes128_ctr(key_128.into(), iv.into());
+    assert_eq!(
+        decrypt(Box::new(aes_128.reader().expect("Reader couldn't be created."))),
+        plaintext
+    );
+    let aes_256 = VecBuilder::from_slice(ciphertext_256).aes256_ctr(key_256.into(), iv.into());
+    assert_eq!(
+        decrypt(Box::new(aes_256.reader().expect("Reader couldn't be created."))),
+        plaintext
+    );
+    test_string(VecBuilder::default().aes128_ctr(key_128.into(), iv.into()));
+    test_string(VecBuilder::default().aes256_ctr(key_256.into(), iv.into()));
+    let key_192 = [0x42; 24];
+    let nonce = [0x24; 16];
+    test_string(
+        VecBuilder::default()
+            .stream_cipher::<ctr::Ctr32BE<aes::Aes192>>(key_192.into(), nonce.into()),
+    );
+}
+
 #[test]
 fn file() {
     let path = temp_dir().join("test_file.txt");
@@ -82,6 +486,226 @@ fn file() {
     assert_eq!(actual, text);
 }
 
+#[test]
+fn file_open_modes() {
+    let path = temp_dir().join("test_file_open_modes.txt");
+    let _ = std::fs::remove_file(&path);
+    let result = (|| -> Result<String> {
+        FileBuilder::new(path.clone()).create_new().string().write_string("Hello world.")?;
+        assert!(FileBuilder::new(path.clone()).create_new().writer().is_err());
+        FileBuilder::new(path.clone()).string().write_string("Hi")?;
+        FileBuilder::new(path.clone()).append().string().write_string(" there.")?;
+        Ok(FileBuilder::new(path.clone()).string().to_string())
+    })();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    assert_eq!(result.expect("Couldn't write file."), "Hi there.");
+}
+
+#[test]
+fn file_atomic() {
+    use std::io::Write;
+    let path = temp_dir().join("test_file_atomic.txt");
+    let builder = FileBuilder::new(path.clone()).atomic().string();
+    let result = (|| -> Result<(String, String)> {
+        builder.write_string("Original contents.")?;
+        let mut writer = FileBuilder::new(path.clone()).atomic().writer()?;
+        writer.write_all(b"Discarded")?;
+        drop(writer);
+        let after_drop = builder.to_string();
+        builder.write_string("New contents.")?;
+        Ok((after_drop, builder.to_string()))
+    })();
+    let leftovers = std::fs::read_dir(temp_dir())
+        .expect("Couldn't list directory.")
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(".test_file_atomic.txt"))
+        .count();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    let (after_drop, after_finish) = result.expect("Couldn't write file.");
+    assert_eq!(after_drop, "Original contents.");
+    assert_eq!(after_finish, "New contents.");
+    assert_eq!(leftovers, 0);
+}
+
+#[test]
+fn file_atomic_write_modes() {
+    let path = temp_dir().join("test_file_atomic_write_modes.txt");
+    let _ = std::fs::remove_file(&path);
+    let result = (|| -> Result<(String, String, Option<std::io::ErrorKind>)> {
+        FileBuilder::new(path.clone()).create_new().atomic().string().write_string("Start")?;
+        FileBuilder::new(path.clone()).append().atomic().string().write_string(" appended")?;
+        let appended = FileBuilder::new(path.clone()).string().to_string();
+        FileBuilder::new(path.clone()).overwrite().atomic().string().write_string("Patched")?;
+        let overwritten = FileBuilder::new(path.clone()).string().to_string();
+        let error = FileBuilder::new(path.clone()).create_new().atomic().writer().err();
+        let kind = error
+            .as_ref()
+            .and_then(|error| error.downcast_ref::<std::io::Error>())
+            .map(std::io::Error::kind);
+        Ok((appended, overwritten, kind))
+    })();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    let (appended, overwritten, kind) = result.expect("Couldn't write file.");
+    assert_eq!(appended, "Start appended");
+    assert_eq!(overwritten, "Patchedppended");
+    assert_eq!(kind, Some(std::io::ErrorKind::AlreadyExists));
+}
+
+#[cfg(unix)]
+#[test]
+fn file_atomic_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+    let path = temp_dir().join("test_file_atomic_permissions.txt");
+    let _ = std::fs::remove_file(&path);
+    let result = (|| -> Result<(u32, u32)> {
+        let builder = FileBuilder::new(path.clone()).mode(0o600).atomic().string();
+        builder.write_string("secret")?;
+        let created = std::fs::metadata(&path)?.permissions().mode();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640))?;
+        FileBuilder::new(path.clone()).atomic().string().write_string("still secret")?;
+        Ok((created, std::fs::metadata(&path)?.permissions().mode()))
+    })();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    let (created, replaced) = result.expect("Couldn't write file.");
+    assert_eq!(created & 0o777, 0o600);
+    assert_eq!(replaced & 0o777, 0o640);
+}
+
+#[cfg(unix)]
+#[test]
+fn file_mode() {
+    use std::os::unix::fs::PermissionsExt;
+    let path = temp_dir().join("test_file_mode.txt");
+    let _ = std::fs::remove_file(&path);
+    let result = (|| -> Result<u32> {
+        FileBuilder::new(path.clone()).mode(0o600).string().write_string("secret")?;
+        Ok(std::fs::metadata(&path)?.permissions().mode())
+    })();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    assert_eq!(result.expect("Couldn't write file.") & 0o777, 0o600);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mmap_file() {
+    let path = temp_dir().join("test_mmap_file.txt");
+    let text = "This text is written into a memory map and read back out of one.".repeat(100);
+    let result = write_and_read_string(MmapFileBuilder::new(path.clone()).size(16), &text);
+    let length = std::fs::metadata(&path).expect("No metadata.").len();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    assert_eq!(result.expect("String couldn't be written"), text);
+    assert_eq!(length, text.len() as u64);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mmap_file_replaced_while_mapped() {
+    let path = temp_dir().join("test_mmap_file_replaced_while_mapped.txt");
+    let builder = MmapFileBuilder::new(path.clone()).string();
+    let result = (|| -> Result<(Vec<u8>, String)> {
+        builder.write_string("The old contents, which stay mapped.")?;
+        let reader = MmapFileBuilder::new(path.clone()).reader()?;
+        builder.write_string("New")?;
+        Ok((reader.as_bytes().to_vec(), builder.to_string()))
+    })();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    let (mapped, replaced) = result.expect("Couldn't replace the file.");
+    assert_eq!(mapped, b"The old contents, which stay mapped.");
+    assert_eq!(replaced, "New");
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mmap_file_unfinished_writer() {
+    use std::io::Write;
+    let path = temp_dir().join("test_mmap_file_unfinished_writer.txt");
+    let builder = MmapFileBuilder::new(path.clone()).string();
+    let result = (|| -> Result<String> {
+        builder.write_string("Kept")?;
+        MmapFileBuilder::new(path.clone()).writer()?.write_all(b"Dropped")?;
+        Ok(builder.to_string())
+    })();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    assert_eq!(result.expect("Couldn't drop the writer."), "Kept");
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mmap_file_writer_at() {
+    use std::io::Write;
+    let path = temp_dir().join("test_mmap_file_writer_at.txt");
+    let builder = MmapFileBuilder::new(path.clone()).size(4);
+    let string = MmapFileBuilder::new(path.clone()).string();
+    let result = (|| -> Result<String> {
+        string.write_string("0123456789")?;
+        let mut writer = builder.writer_at(2)?;
+        writer.write_all(b"ab")?;
+        writer.finish()?;
+        Ok(string.to_string())
+    })();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    assert_eq!(result.expect("Couldn't write at the offset."), "01ab456789");
+}
+
+#[cfg(all(feature = "mmap", feature = "chacha20", feature = "bincode"))]
+#[test]
+fn mmap_file_zero_copy() {
+    use std::io::Write;
+    let path = temp_dir().join("test_mmap_file_zero_copy.bin");
+    let builder = MmapFileBuilder::new(path.clone());
+    let result = (|| -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut writer = builder.writer()?;
+        writer.write_all(b"raw bytes")?;
+        writer.finish()?;
+        let raw = builder.reader()?.as_bytes().to_vec();
+        let chained = MmapFileBuilder::new(path.clone())
+            .chacha20([0x42; 32].into(), [0x24; 12].into())
+            .bincode();
+        chained.save(&raw)?;
+        Ok((raw, chained.load()?))
+    })();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    let (raw, loaded) = result.expect("Couldn't use the memory map.");
+    assert_eq!(raw, b"raw bytes");
+    assert_eq!(loaded, raw);
+}
+
+#[test]
+fn seek() {
+    use std::io::{Read, Write};
+    let path = temp_dir().join("test_seek.txt");
+    let builder = FileBuilder::new(path.clone()).overwrite().buffered();
+    let result = (|| -> Result<String> {
+        builder.writer()?.write_all(b"0123456789")?;
+        let mut writer = builder.writer_at(2)?;
+        writer.write_all(b"ab")?;
+        writer.finish()?;
+        let mut actual = String::new();
+        let _ = builder.reader_at(1)?.read_to_string(&mut actual)?;
+        Ok(actual)
+    })();
+    std::fs::remove_file(path).expect("File couldn't be removed.");
+    assert_eq!(result.expect("Couldn't seek."), "1ab456789");
+}
+
+#[cfg(feature = "chacha20")]
+#[test]
+fn seek_chacha20() {
+    use std::io::{Read, Write};
+    let key = [0x42; 32];
+    let nonce = [0x24; 12];
+    let data: Vec<u8> = (0..=255).collect();
+    let builder = VecBuilder::default().chacha20(key.into(), nonce.into());
+    let mut writer = builder.writer().expect("Writer couldn't be created.");
+    writer.write_all(&data[..100]).expect("Couldn't write data.");
+    writer = builder.writer_at(100).expect("Writer couldn't be created.");
+    writer.write_all(&data[100..]).expect("Couldn't write data.");
+    let mut buffer = vec![];
+    let mut reader = builder.reader_at(150).expect("Reader couldn't be created.");
+    let _ = reader.read_to_end(&mut buffer).expect("Couldn't read into buffer.");
+    assert_eq!(buffer, data[150..]);
+}
+
 #[test]
 fn process_stdout() {
     let mut command = Command::new("rustc");
@@ -90,6 +714,21 @@ fn process_stdout() {
     assert!(help.starts_with("Usage: rustc"));
 }
 
+#[cfg(unix)]
+#[test]
+fn process_reader_dropped_early() {
+    use std::io::Read;
+    let mut command = Command::new("sh");
+    let _ = command.args(["-c", "echo started; sleep 10"]);
+    let builder = ProcessBuilder::new(command);
+    let start = std::time::Instant::now();
+    let mut reader = builder.reader().expect("Reader couldn't be created.");
+    let mut buffer = [0u8; 8];
+    reader.read_exact(&mut buffer).expect("Couldn't read from process.");
+    drop(reader);
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+}
+
 #[cfg(target_os = "linux")]
 #[test]
 fn process_child() {
@@ -99,6 +738,263 @@ fn process_child() {
     assert_eq!(builder.to_string(), "Hello world.\n");
 }
 
+#[test]
+fn vec_accessors() {
+    use std::io::{Read, Write};
+    let builder = VecBuilder::from_slice(b"Hello");
+    let mut text = String::new();
+    let _ = builder.reader().expect("Reader couldn't be created.").read_to_string(&mut text);
+    assert_eq!(text, "Hello");
+    let mut writer = builder.writer().expect("Writer couldn't be created.");
+    writer.write_all(b" world.").expect("Couldn't write bytes.");
+    assert_eq!(builder.len(), 12);
+    assert_eq!(builder.snapshot(), b"Hello world.");
+    let mut reader = builder.reader().expect("Reader couldn't be created.");
+    let _ = reader.read_to_string(&mut text);
+    builder.clear();
+    assert!(builder.is_empty());
+    writer.write_all(b"Bye.").expect("Couldn't write bytes.");
+    text.clear();
+    let _ = reader.read_to_string(&mut text);
+    assert_eq!(text, "Bye.");
+    assert_eq!(builder.into_inner(), b"Bye.");
+    drop(writer);
+    assert_eq!(VecBuilder::from(b"Payload".to_vec()).into_inner(), b"Payload");
+}
+
+#[test]
+fn sync_vec() {
+    test_string(SyncVecBuilder::default());
+}
+
+#[test]
+fn sync_vec_pipe() {
+    use std::io::{Read, Write};
+    let builder = SyncVecBuilder::default().pipe();
+    let producer = builder.clone();
+    let thread = std::thread::spawn(move || -> Result<()> {
+        let mut writer = producer.writer()?;
+        for word in ["Hello ", "from ", "another ", "thread."] {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            writer.write_all(word.as_bytes())?;
+        }
+        writer.finish()
+    });
+    let mut reader = builder.reader().expect("Reader couldn't be created.");
+    let mut output = String::new();
+    let _ = reader.read_to_string(&mut output).expect("Couldn't read from the pipe.");
+    assert_eq!(output, "Hello from another thread.");
+    thread.join().expect("Thread panicked.").expect("Couldn't write to the pipe.");
+}
+
+#[test]
+fn sync_vec_pipe_two_writers() {
+    use std::io::{Read, Write};
+    let builder = SyncVecBuilder::default().pipe();
+    let mut first = builder.writer().expect("Writer couldn't be created.");
+    first.write_all(b"Both ").expect("Couldn't write to the pipe.");
+    let mut second = builder.writer().expect("Writer couldn't be created.");
+    first.finish().expect("Couldn't finish the writer.");
+    let thread = std::thread::spawn(move || -> Result<()> {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        second.write_all(b"writers.")?;
+        second.finish()
+    });
+    let mut reader = builder.reader().expect("Reader couldn't be created.");
+    let mut output = String::new();
+    let _ = reader.read_to_string(&mut output).expect("Couldn't read from the pipe.");
+    assert_eq!(output, "Both writers.");
+    thread.join().expect("Thread panicked.").expect("Couldn't write to the pipe.");
+}
+
+/// Set in the environment of the test binary when it is run as a filter by the
+/// `stdio` test
+const STDIO_FILTER: &str = "RW_BUILDER_STDIO_FILTER";
+
+#[test]
+fn stdio() {
+    use std::io::Write;
+    if std::env::var_os(STDIO_FILTER).is_some() {
+        // The test harness writes to stdout as well, so the filtered bytes
+        // are put between brackets
+        let input = StdioBuilder.string().to_string();
+        StdioBuilder.string().write_string(&format!("[{input}]")).expect("Couldn't write.");
+        StderrBuilder.string().write_string("{diagnostics}").expect("Couldn't write.");
+        return;
+    }
+    let mut child = Command::new(std::env::current_exe().expect("No test binary."))
+        .args(["tests::stdio", "--exact", "--test-threads=1"])
+        .env(STDIO_FILTER, "1")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Couldn't run the test binary.");
+    let mut stdin = child.stdin.take().expect("No stdin.");
+    stdin.write_all(b"Filtered through stdio.").expect("Couldn't write to the filter.");
+    drop(stdin);
+    let output = child.wait_with_output().expect("The filter failed.");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("[Filtered through stdio.]"), "{stdout}");
+    assert!(stderr.contains("{diagnostics}"), "{stderr}");
+}
+
+#[test]
+fn tcp_listener() {
+    let listener = TcpListenerBuilder::bind("127.0.0.1:0").expect("Couldn't bind listener.");
+    let client = TcpStreamBuilder::new(listener.local_addr().expect("No local address.")).string();
+    let thread = std::thread::spawn(move || client.write_string("Hello listener."));
+    assert_eq!(listener.string().to_string(), "Hello listener.");
+    thread.join().expect("Thread panicked.").expect("Couldn't write string.");
+}
+
+#[test]
+fn tcp_listener_accept() {
+    use std::io::{Read, Write};
+    let listener = TcpListenerBuilder::bind("127.0.0.1:0").expect("Couldn't bind listener.");
+    let addr = listener.local_addr().expect("No local address.");
+    let thread = std::thread::spawn(move || -> Result<String> {
+        let mut stream = std::net::TcpStream::connect(addr)?;
+        stream.write_all(b"ping")?;
+        stream.shutdown(std::net::Shutdown::Write)?;
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response)?;
+        Ok(response)
+    });
+    let connection = listener.accept().expect("Couldn't accept connection.").string();
+    let request = connection.to_string();
+    connection.write_string(&format!("{request} pong")).expect("Couldn't write string.");
+    let response = thread.join().expect("Thread panicked.").expect("Client failed.");
+    assert_eq!(response, "ping pong");
+}
+
+#[test]
+fn tcp_listener_timeout() {
+    let listener = TcpListenerBuilder::bind("127.0.0.1:0")
+        .expect("Couldn't bind listener.")
+        .timeout(std::time::Duration::from_millis(20));
+    let error = listener.reader().expect_err("No connection should have been accepted.");
+    let error = error.downcast::<std::io::Error>().expect("Not an I/O error.");
+    assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+}
+
+#[test]
+fn tcp_listener_timeout_accepted() {
+    let listener = TcpListenerBuilder::bind("127.0.0.1:0")
+        .expect("Couldn't bind listener.")
+        .timeout(std::time::Duration::from_secs(5));
+    let addr = listener.local_addr().expect("No local address.");
+    let client = std::net::TcpStream::connect(addr).expect("Couldn't connect.");
+    let stream = listener.reader().expect("No connection was accepted.");
+    assert_eq!(stream.read_timeout().expect("Couldn't query the timeout."), None);
+    drop(client);
+}
+
+#[test]
+fn tcp_stream_connect() {
+    let listener = TcpListenerBuilder::bind("127.0.0.1:0").expect("Couldn't bind listener.");
+    let addr = listener.local_addr().expect("No local address.");
+    let thread = std::thread::spawn(move || -> Result<()> {
+        let connection = listener.accept()?.string();
+        let request = connection.to_string();
+        connection.write_string(&format!("{request} pong"))
+    });
+    let timeout = std::time::Duration::from_secs(5);
+    let client = TcpStreamBuilder::new(addr)
+        .connect_timeout(timeout)
+        .read_timeout(timeout)
+        .write_timeout(timeout)
+        .nodelay()
+        .connect()
+        .expect("Couldn't connect.")
+        .string();
+    client.write_string("ping").expect("Couldn't write string.");
+    assert_eq!(client.to_string(), "ping pong");
+    thread.join().expect("Thread panicked.").expect("Server failed.");
+}
+
+#[cfg(unix)]
+#[test]
+fn pipe_through() {
+    let mut encode = Command::new("base64");
+    let _ = encode.arg("-w0");
+    let mut decode = Command::new("base64");
+    let _ = decode.arg("-d");
+    let builder = SyncVecBuilder::default();
+    let text = "This text is encoded by one command and decoded by another.";
+    let piped = builder.clone().pipe_through(encode, decode);
+    assert_eq!(write_and_read_string(piped, text).expect("Couldn't pipe string."), text);
+    assert_eq!(
+        builder.string().to_string(),
+        "VGhpcyB0ZXh0IGlzIGVuY29kZWQgYnkgb25lIGNvbW1hbmQgYW5kIGRlY29kZWQgYnkgYW5vdGhlci4="
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn process_error() {
+    use std::io::Read;
+    let process_error = |error: &anyhow::Error| -> Option<ProcessError> {
+        error.downcast_ref::<std::io::Error>()?.get_ref()?.downcast_ref::<ProcessError>().cloned()
+    };
+    let mut command = Command::new("sh");
+    let _ = command.args(["-c", "echo output; echo failure >&2; exit 3"]);
+    let builder = ProcessBuilder::new(command);
+    let mut output = String::new();
+    let result = builder.reader().and_then(|mut reader| Ok(reader.read_to_string(&mut output)?));
+    let error =
+        process_error(&result.expect_err("Reading should fail.")).expect("No process error.");
+    assert_eq!(output, "output\n");
+    assert_eq!(error.status.code(), Some(3));
+    assert_eq!(error.stderr, b"failure\n");
+
+    let mut command = Command::new("sh");
+    let _ = command.args(["-c", "cat > /dev/null; echo rejected >&2; exit 2"]);
+    let builder = ProcessBuilder::new(command).string();
+    let error = builder.write_string("input").expect_err("Writing should fail.");
+    let error = process_error(&error).expect("No process error.");
+    assert_eq!(error.status.code(), Some(2));
+    assert_eq!(error.to_string(), "the child process failed with exit status: 2: rejected");
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_stream() {
+    let path = temp_dir().join("test_unix_stream.sock");
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListenerBuilder::bind(&path).expect("Couldn't bind socket.").string();
+    let client = UnixStreamBuilder::new(path.clone()).string();
+    let thread = std::thread::spawn(move || client.write_string("Hello listener."));
+    assert_eq!(listener.to_string(), "Hello listener.");
+    thread.join().expect("Thread panicked.").expect("Couldn't write string.");
+    std::fs::remove_file(path).expect("Socket couldn't be removed.");
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_datagram() {
+    use std::io::{Read, Write};
+    let path = temp_dir().join("test_unix_datagram.sock");
+    let _ = std::fs::remove_file(&path);
+    let builder = UnixDatagramBuilder::new(path.clone());
+    let mut reader = builder.reader().expect("Reader couldn't be created.");
+    let mut writer = builder.writer().expect("Writer couldn't be created.");
+    writer.write_all(b"first").expect("Couldn't write datagram.");
+    writer.write_all(b"second").expect("Couldn't write datagram.");
+    let mut buffer = [0; 16];
+    assert_eq!(reader.read(&mut buffer).expect("Couldn't read datagram."), 5);
+    assert_eq!(&buffer[..5], b"first");
+    assert_eq!(reader.read(&mut buffer).expect("Couldn't read datagram."), 6);
+    assert_eq!(&buffer[..6], b"second");
+    writer.write_all(b"too long").expect("Couldn't write datagram.");
+    let error = reader.read(&mut buffer[..4]).expect_err("The datagram should be truncated.");
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    drop(reader);
+    assert!(!path.exists());
+}
+
 #[cfg(feature = "bincode")]
 #[test]
 fn bincode() {
@@ -108,3 +1004,257 @@ fn bincode() {
     let actual: String = builder.load().expect("Deserialization failed.");
     assert_eq!(actual, text);
 }
+
+#[cfg(feature = "json")]
+#[test]
+fn json() {
+    let builder = VecBuilder::default().json();
+    let value = vec![(String::from("key"), 42), (String::from("other key"), 1337)];
+    builder.save(&value).expect("Serialization failed.");
+    assert_eq!(builder.load::<Vec<(String, i32)>>().expect("Deserialization failed."), value);
+    let builder = VecBuilder::default().json().pretty();
+    builder.save(&value).expect("Serialization failed.");
+    assert_eq!(builder.load::<Vec<(String, i32)>>().expect("Deserialization failed."), value);
+}
+
+#[cfg(all(feature = "flate2", feature = "json"))]
+#[test]
+fn json_gz() {
+    let builder = VecBuilder::default().gz(Compression::fast()).json().pretty();
+    let text = "This string is serialized and deserialized using JSON.";
+    builder.save(&text).expect("Serialization failed.");
+    let actual: String = builder.load().expect("Deserialization failed.");
+    assert_eq!(actual, text);
+}
+
+#[cfg(all(feature = "flate2", feature = "json"))]
+#[test]
+fn ndjson() {
+    let builder = VecBuilder::default().gz(Compression::fast()).ndjson();
+    let mut writer = builder.record_writer().expect("Writer couldn't be created.");
+    for index in 0..1000 {
+        writer.push(&(index, format!("record {index}"))).expect("Serialization failed.");
+    }
+    writer.finish().expect("Couldn't finish writer.");
+    let records = builder.records::<(i32, String)>().expect("Reader couldn't be created.");
+    for (index, record) in (0..1000).zip(records) {
+        assert_eq!(record.expect("Deserialization failed."), (index, format!("record {index}")));
+    }
+    assert_eq!(
+        builder.records::<(i32, String)>().expect("Reader couldn't be created.").count(),
+        1000
+    );
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn bincode_records() {
+    let builder = VecBuilder::default().bincode_records();
+    let mut writer = builder.record_writer().expect("Writer couldn't be created.");
+    for index in 0..1000 {
+        writer.push(&vec![index; index]).expect("Serialization failed.");
+    }
+    writer.finish().expect("Couldn't finish writer.");
+    let records: Vec<Vec<usize>> = builder
+        .records()
+        .expect("Reader couldn't be created.")
+        .collect::<Result<_>>()
+        .expect("Deserialization failed.");
+    assert_eq!(records.len(), 1000);
+    assert!(records.iter().enumerate().all(|(index, record)| *record == vec![index; index]));
+    let mut corrupt = u64::MAX.to_le_bytes().to_vec();
+    corrupt.extend_from_slice(b"short");
+    let mut records = VecBuilder::from(corrupt)
+        .bincode_records()
+        .records::<Vec<u8>>()
+        .expect("Reader couldn't be created.");
+    let error = records.next().expect("No record.").expect_err("The record should be truncated.");
+    let kind = error.downcast_ref::<std::io::Error>().map(std::io::Error::kind);
+    assert_eq!(kind, Some(std::io::ErrorKind::UnexpectedEof));
+    assert!(records.next().is_none());
+}
+
+#[cfg(feature = "async-tokio")]
+mod async_tokio {
+    use std::{env::temp_dir, future::Future, process::Command};
+
+    #[cfg(feature = "flate2")]
+    use ::flate2::Compression;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[cfg(any(feature = "bincode", feature = "json"))]
+    use crate::AsyncSerDe;
+    use crate::{
+        AsyncRwBuilder, FileBuilder, ProcessBuilder, Result, RwBuilder, TcpStreamBuilder,
+        VecBuilder,
+    };
+
+    fn block_on<F>(future: F) -> F::Output
+    where
+        F: Future,
+    {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Runtime couldn't be created.")
+            .block_on(future)
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn write_and_read_bytes<B>(builder: &B, input: &[u8]) -> Result<Vec<u8>>
+    where
+        B: AsyncRwBuilder,
+    {
+        let mut writer = builder.async_writer().await?;
+        writer.write_all(input).await?;
+        writer.shutdown().await?;
+        let mut output = vec![];
+        let _ = builder.async_reader().await?.read_to_end(&mut output).await?;
+        Ok(output)
+    }
+
+    fn test_bytes<B>(builder: &B)
+    where
+        B: AsyncRwBuilder,
+    {
+        let text = b"These bytes are written asynchronously and read back asynchronously.";
+        let actual =
+            block_on(write_and_read_bytes(builder, text)).expect("Bytes couldn't be written");
+        assert_eq!(actual, text);
+    }
+
+    #[test]
+    fn vec() {
+        test_bytes(&VecBuilder::default().buffered());
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn compression() {
+        test_bytes(&VecBuilder::default().zlib(Compression::fast()));
+        test_bytes(&VecBuilder::default().gz(Compression::fast()));
+        test_bytes(&VecBuilder::default().deflate(Compression::fast()));
+    }
+
+    #[cfg(feature = "chacha20")]
+    #[test]
+    fn chacha20() {
+        let key = [0x42; 32];
+        let nonce = [0x24; 12];
+        test_bytes(&VecBuilder::default().chacha20(key.into(), nonce.into()));
+    }
+
+    #[cfg(feature = "salsa20")]
+    #[test]
+    fn salsa20() {
+        let key = [0x42; 32];
+        let nonce = [0x24; 8];
+        test_bytes(&VecBuilder::default().salsa20(key.into(), nonce.into()));
+    }
+
+    #[test]
+    fn file() {
+        let path = temp_dir().join("test_async_file.txt");
+        let builder = FileBuilder::new(path.clone()).buffered();
+        test_bytes(&builder);
+        std::fs::remove_file(path).expect("File couldn't be removed.");
+    }
+
+    #[test]
+    fn process_stdout() {
+        let mut command = Command::new("rustc");
+        let _ = command.arg("--help");
+        let builder = ProcessBuilder::new(command);
+        let help = block_on(async {
+            let mut help = String::new();
+            let _ = builder.async_reader().await?.read_to_string(&mut help).await?;
+            Result::<String>::Ok(help)
+        })
+        .expect("Couldn't read from process.");
+        assert!(help.starts_with("Usage: rustc"));
+    }
+
+    #[test]
+    fn tcp_stream() {
+        use std::io::Read;
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("Couldn't bind.");
+        let addr = listener.local_addr().expect("No local address.");
+        let builder = TcpStreamBuilder::new(addr)
+            .connect_timeout(std::time::Duration::from_secs(5))
+            .nodelay();
+        block_on(async {
+            let mut writer = builder.async_writer().await?;
+            writer.write_all(b"async ping").await?;
+            writer.shutdown().await?;
+            Result::<()>::Ok(())
+        })
+        .expect("Couldn't write to the stream.");
+        let mut request = String::new();
+        let (mut stream, _) = listener.accept().expect("Couldn't accept connection.");
+        let _ = stream.read_to_string(&mut request).expect("Couldn't read request.");
+        assert_eq!(request, "async ping");
+        let builder = TcpStreamBuilder::new(addr).read_timeout(std::time::Duration::from_secs(5));
+        let error = block_on(builder.async_reader()).expect_err("Read timeouts aren't supported.");
+        let kind = error.downcast_ref::<std::io::Error>().map(std::io::Error::kind);
+        assert_eq!(kind, Some(std::io::ErrorKind::Unsupported));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn process_error() {
+        let exit_code = |error: &anyhow::Error| -> Option<i32> {
+            let error = error.downcast_ref::<std::io::Error>()?.get_ref()?;
+            error.downcast_ref::<crate::ProcessError>()?.status.code()
+        };
+        let mut command = Command::new("sh");
+        let _ = command.args(["-c", "echo output; exit 3"]);
+        let builder = ProcessBuilder::new(command);
+        let mut output = vec![];
+        let error = block_on(async {
+            let _ = builder.async_reader().await?.read_to_end(&mut output).await?;
+            Result::<()>::Ok(())
+        })
+        .expect_err("Reading should fail.");
+        assert_eq!(output, b"output\n");
+        assert_eq!(exit_code(&error), Some(3));
+
+        let mut command = Command::new("sh");
+        let _ = command.args(["-c", "cat > /dev/null; exit 2"]);
+        let builder = ProcessBuilder::new(command);
+        let error = block_on(async {
+            let mut writer = builder.async_writer().await?;
+            writer.write_all(b"input").await?;
+            writer.shutdown().await?;
+            Result::<()>::Ok(())
+        })
+        .expect_err("Writing should fail.");
+        assert_eq!(exit_code(&error), Some(2));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn process_child() {
+        let builder = ProcessBuilder::new(Command::new("tee")).spawn().expect("Couldn't spawn.");
+        test_bytes(&builder);
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn bincode() {
+        let builder = VecBuilder::default().bincode();
+        let text = "This string is serialized and deserialized using bincode.";
+        block_on(builder.async_save(&text)).expect("Serialization failed.");
+        let actual: String = block_on(builder.async_load()).expect("Deserialization failed.");
+        assert_eq!(actual, text);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json() {
+        let builder = VecBuilder::default().json().pretty();
+        let text = "This string is serialized and deserialized using JSON.";
+        block_on(builder.async_save(&text)).expect("Serialization failed.");
+        let actual: String = block_on(builder.async_load()).expect("Deserialization failed.");
+        assert_eq!(actual, text);
+    }
+}
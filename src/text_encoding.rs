@@ -0,0 +1,331 @@
+use std::{
+    fmt::{Debug, Formatter},
+    io::{Read, Write},
+};
+
+use anyhow::Result;
+#[cfg(feature = "base64")]
+use base64::Engine;
+
+use crate::{Finish, RwBuilder};
+
+/// The number of encoded bytes the reader reads from the inner reader at once
+const READ_SIZE: usize = 8 * 1024;
+
+/// Implementors like `Base64`, `Hex` and `Base32` define how blocks of bytes
+/// are encoded as printable text.
+pub trait TextEncoding {
+    /// The number of bytes which are encoded as one block of text
+    const BLOCK_SIZE: usize;
+
+    /// The number of characters in one encoded block
+    const ENCODED_BLOCK_SIZE: usize;
+
+    /// Append the encoding of `input` to `output`, where the input is a
+    /// multiple of `BLOCK_SIZE` unless it's the end of the stream
+    fn encode(&self, input: &[u8], output: &mut Vec<u8>);
+
+    /// Append the decoding of `input` to `output`, where the input is a
+    /// multiple of `ENCODED_BLOCK_SIZE` unless it's the end of the stream
+    /// # Errors
+    /// Fails with `ErrorKind::InvalidData` if the input isn't valid.
+    fn decode(&self, input: &[u8], output: &mut Vec<u8>) -> std::io::Result<()>;
+}
+
+/// The base64 encoding, configured through a `base64` engine like
+/// `general_purpose::STANDARD` or `general_purpose::URL_SAFE_NO_PAD`
+#[cfg(feature = "base64")]
+#[derive(Debug, Clone)]
+pub struct Base64(pub base64::engine::GeneralPurpose);
+
+#[cfg(feature = "base64")]
+impl TextEncoding for Base64 {
+    const BLOCK_SIZE: usize = 3;
+    const ENCODED_BLOCK_SIZE: usize = 4;
+
+    fn encode(&self, input: &[u8], output: &mut Vec<u8>) {
+        output.extend_from_slice(self.0.encode(input).as_bytes());
+    }
+
+    fn decode(&self, input: &[u8], output: &mut Vec<u8>) -> std::io::Result<()> {
+        self.0.decode_vec(input, output).map_err(invalid_data)
+    }
+}
+
+/// The lowercase hexadecimal encoding, which decodes both cases
+#[cfg(feature = "hex")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Hex;
+
+#[cfg(feature = "hex")]
+impl TextEncoding for Hex {
+    const BLOCK_SIZE: usize = 1;
+    const ENCODED_BLOCK_SIZE: usize = 2;
+
+    fn encode(&self, input: &[u8], output: &mut Vec<u8>) {
+        encode_append(&data_encoding::HEXLOWER, input, output);
+    }
+
+    fn decode(&self, input: &[u8], output: &mut Vec<u8>) -> std::io::Result<()> {
+        decode_append(&data_encoding::HEXLOWER_PERMISSIVE, input, output)
+    }
+}
+
+/// The padded base32 encoding of RFC 4648
+#[cfg(feature = "base32")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Base32;
+
+#[cfg(feature = "base32")]
+impl TextEncoding for Base32 {
+    const BLOCK_SIZE: usize = 5;
+    const ENCODED_BLOCK_SIZE: usize = 8;
+
+    fn encode(&self, input: &[u8], output: &mut Vec<u8>) {
+        encode_append(&data_encoding::BASE32, input, output);
+    }
+
+    fn decode(&self, input: &[u8], output: &mut Vec<u8>) -> std::io::Result<()> {
+        decode_append(&data_encoding::BASE32, input, output)
+    }
+}
+
+/// Convert a decoding error into an I/O error
+fn invalid_data<E>(error: E) -> std::io::Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+}
+
+/// Encode `input` with a `data_encoding` encoding and append it to `output`
+#[cfg(any(feature = "base32", feature = "hex"))]
+fn encode_append(encoding: &data_encoding::Encoding, input: &[u8], output: &mut Vec<u8>) {
+    let start = output.len();
+    output.resize(start + encoding.encode_len(input.len()), 0);
+    encoding.encode_mut(input, &mut output[start..]);
+}
+
+/// Decode `input` with a `data_encoding` encoding and append it to `output`
+#[cfg(any(feature = "base32", feature = "hex"))]
+fn decode_append(
+    encoding: &data_encoding::Encoding,
+    input: &[u8],
+    output: &mut Vec<u8>,
+) -> std::io::Result<()> {
+    let start = output.len();
+    output.resize(start + encoding.decode_len(input.len()).map_err(invalid_data)?, 0);
+    let length = encoding
+        .decode_mut(input, &mut output[start..])
+        .map_err(|partial| invalid_data(partial.error))?;
+    output.truncate(start + length);
+    Ok(())
+}
+
+/// Type returned by the `base64`, `hex` and `base32` functions on the
+/// `RwBuilder` trait. It is itself an `RwBuilder` so can be chained further.
+#[derive(Debug)]
+pub struct Builder<B, E> {
+    /// The inner builder it wraps
+    builder: B,
+    /// The encoding used by the readers and writers
+    encoding: E,
+}
+
+impl<B, E> Builder<B, E> {
+    /// Factory function to wrap an inner builder
+    #[must_use]
+    pub const fn new(builder: B, encoding: E) -> Self {
+        Self { builder, encoding }
+    }
+}
+
+impl<B, E> RwBuilder for Builder<B, E>
+where
+    B: RwBuilder,
+    E: TextEncoding + Clone,
+{
+    type Reader = Reader<B::Reader, E>;
+    type Writer = Writer<B::Writer, E>;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        Ok(Reader {
+            reader: self.builder.reader()?,
+            encoding: self.encoding.clone(),
+            encoded: vec![],
+            decoded: vec![],
+            position: 0,
+            done: false,
+        })
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        Ok(Writer {
+            writer: Some(self.builder.writer()?),
+            encoding: self.encoding.clone(),
+            pending: vec![],
+            encoded: vec![],
+        })
+    }
+}
+
+/// Reader that decodes the text read from the inner reader, skipping ASCII
+/// whitespace like line breaks
+#[allow(clippy::struct_field_names)]
+pub struct Reader<R, E> {
+    /// The wrapped reader
+    reader: R,
+    /// The encoding of the text
+    encoding: E,
+    /// Encoded text which doesn't form a whole block yet
+    encoded: Vec<u8>,
+    /// The decoded bytes which weren't read yet
+    decoded: Vec<u8>,
+    /// The read position in the decoded bytes
+    position: usize,
+    /// Whether the inner reader reached its end
+    done: bool,
+}
+
+impl<R, E> Debug for Reader<R, E>
+where
+    R: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Reader").field("reader", &self.reader).finish_non_exhaustive()
+    }
+}
+
+impl<R, E> Reader<R, E>
+where
+    R: Read,
+    E: TextEncoding,
+{
+    /// Read more text and decode all whole blocks, or the rest of the text at
+    /// the end of the stream
+    fn decode_more(&mut self) -> std::io::Result<()> {
+        let mut buffer = [0u8; READ_SIZE];
+        let byte_count = self.reader.read(&mut buffer)?;
+        self.done = byte_count == 0;
+        self.encoded.extend(buffer[..byte_count].iter().filter(|byte| !byte.is_ascii_whitespace()));
+        let length = if self.done {
+            self.encoded.len()
+        } else {
+            self.encoded.len() - self.encoded.len() % E::ENCODED_BLOCK_SIZE
+        };
+        self.decoded.clear();
+        self.position = 0;
+        self.encoding.decode(&self.encoded[..length], &mut self.decoded)?;
+        let _ = self.encoded.drain(..length);
+        Ok(())
+    }
+}
+
+impl<R, E> Read for Reader<R, E>
+where
+    R: Read,
+    E: TextEncoding,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.decoded.len() && !self.done && !buf.is_empty() {
+            self.decode_more()?;
+        }
+        let byte_count = (self.decoded.len() - self.position).min(buf.len());
+        buf[..byte_count].copy_from_slice(&self.decoded[self.position..self.position + byte_count]);
+        self.position += byte_count;
+        Ok(byte_count)
+    }
+}
+
+/// Writer that encodes whole blocks as text as soon as possible
+/// The last partial block is only encoded when the writer is finished or
+/// dropped.
+#[allow(clippy::struct_field_names)]
+pub struct Writer<W, E>
+where
+    W: Write,
+    E: TextEncoding,
+{
+    /// The wrapped writer, which is taken when the writer is finished
+    writer: Option<W>,
+    /// The encoding of the text
+    encoding: E,
+    /// Bytes which don't form a whole block yet
+    pending: Vec<u8>,
+    /// Scratch space for the encoded text
+    encoded: Vec<u8>,
+}
+
+impl<W, E> Debug for Writer<W, E>
+where
+    W: Write + Debug,
+    E: TextEncoding,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Writer").field("writer", &self.writer).finish_non_exhaustive()
+    }
+}
+
+impl<W, E> Writer<W, E>
+where
+    W: Write,
+    E: TextEncoding,
+{
+    /// Encode `input` and write it to the inner writer
+    fn write_encoded(&mut self, input: &[u8]) -> std::io::Result<()> {
+        let Some(writer) = self.writer.as_mut() else {
+            return Err(std::io::Error::other("the encoded stream was already finished"));
+        };
+        self.encoded.clear();
+        self.encoding.encode(input, &mut self.encoded);
+        writer.write_all(&self.encoded)
+    }
+
+    /// Encode the last partial block
+    fn write_pending(&mut self) -> std::io::Result<()> {
+        let pending = std::mem::take(&mut self.pending);
+        if pending.is_empty() { Ok(()) } else { self.write_encoded(&pending) }
+    }
+}
+
+impl<W, E> Write for Writer<W, E>
+where
+    W: Write,
+    E: TextEncoding,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        let length = self.pending.len() - self.pending.len() % E::BLOCK_SIZE;
+        if length > 0 {
+            let blocks: Vec<u8> = self.pending.drain(..length).collect();
+            self.write_encoded(&blocks)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.as_mut().map_or(Ok(()), Write::flush)
+    }
+}
+
+impl<W, E> Finish for Writer<W, E>
+where
+    W: Write + Finish,
+    E: TextEncoding,
+{
+    fn finish(mut self) -> Result<()> {
+        self.write_pending()?;
+        self.writer.take().map_or(Ok(()), Finish::finish)
+    }
+}
+
+impl<W, E> Drop for Writer<W, E>
+where
+    W: Write,
+    E: TextEncoding,
+{
+    fn drop(&mut self) {
+        // Errors can't be reported while dropping, use `finish` instead
+        let _ = self.write_pending();
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    io::{Read, Write},
+    net::Shutdown,
+    os::unix::net::{UnixDatagram, UnixListener, UnixStream},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+
+use crate::{Finish, RwBuilder};
+
+/// Type for building readers and writers on top of a connected Unix domain
+/// socket. It is itself an `RwBuilder`, but can't be created through one.
+/// This is why we call it a source.
+#[derive(Debug)]
+pub struct Builder {
+    /// The path of the socket to connect to
+    path: PathBuf,
+}
+
+impl Builder {
+    /// Factory function to create a builder holding on to a socket path
+    #[must_use]
+    pub const fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl RwBuilder for Builder {
+    type Reader = UnixStream;
+    type Writer = UnixStream;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        let stream = UnixStream::connect(&self.path)?;
+        Ok(stream)
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        let stream = UnixStream::connect(&self.path)?;
+        Ok(stream)
+    }
+}
+
+impl Finish for UnixStream {
+    /// Flushes the stream and shuts down its writing half so the peer reads
+    /// the end of the stream.
+    fn finish(mut self) -> Result<()> {
+        self.flush()?;
+        Ok(self.shutdown(Shutdown::Write)?)
+    }
+}
+
+/// Type for building readers and writers on top of the connections accepted
+/// by a listening Unix domain socket.
+///
+/// Every reader and writer accepts a new connection. It is itself an
+/// `RwBuilder`, but can't be created through one. This is why we call it a
+/// source.
+#[derive(Debug)]
+pub struct ListenerBuilder {
+    /// The socket listening for connections
+    listener: UnixListener,
+}
+
+impl ListenerBuilder {
+    /// Create a builder listening on a socket at the given path
+    /// # Errors
+    /// Propagates the error of failing to bind the socket, e.g. because the
+    /// path already exists.
+    pub fn bind<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self { listener: UnixListener::bind(path)? })
+    }
+}
+
+impl RwBuilder for ListenerBuilder {
+    type Reader = UnixStream;
+    type Writer = UnixStream;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        let (stream, _) = self.listener.accept()?;
+        Ok(stream)
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        let (stream, _) = self.listener.accept()?;
+        Ok(stream)
+    }
+}
+
+/// Type for building readers and writers on top of a Unix datagram socket.
+///
+/// The reader binds the socket to the path and the writer sends to it, so
+/// every write is sent as a single datagram. It is itself an `RwBuilder`, but
+/// can't be created through one. This is why we call it a source.
+#[derive(Debug)]
+pub struct DatagramBuilder {
+    /// The path of the socket receiving the datagrams
+    path: PathBuf,
+}
+
+impl DatagramBuilder {
+    /// Factory function to create a builder holding on to a socket path
+    #[must_use]
+    pub const fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl RwBuilder for DatagramBuilder {
+    type Reader = DatagramReader;
+    type Writer = DatagramWriter;
+
+    fn reader(&self) -> Result<Self::Reader> {
+        let socket = UnixDatagram::bind(&self.path)?;
+        Ok(DatagramReader { socket, path: self.path.clone(), buffer: Vec::new() })
+    }
+
+    fn writer(&self) -> Result<Self::Writer> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(&self.path)?;
+        Ok(DatagramWriter { socket })
+    }
+}
+
+/// Reading the received datagrams
+///
+/// Reading a datagram that doesn't fit in the buffer passed to `read` fails
+/// with `ErrorKind::InvalidData`, the datagram is lost. The socket file is
+/// removed when the reader is dropped.
+#[derive(Debug)]
+pub struct DatagramReader {
+    /// The socket bound to the path
+    socket: UnixDatagram,
+    /// The path the socket is bound to
+    path: PathBuf,
+    /// Receives the datagrams, one byte longer than the buffer passed to
+    /// `read` to detect truncation
+    buffer: Vec<u8>,
+}
+
+impl Read for DatagramReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.buffer.resize(buf.len() + 1, 0);
+        let length = self.socket.recv(&mut self.buffer)?;
+        if length > buf.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "the datagram doesn't fit in the buffer",
+            ));
+        }
+        buf[..length].copy_from_slice(&self.buffer[..length]);
+        Ok(length)
+    }
+}
+
+impl Drop for DatagramReader {
+    fn drop(&mut self) {
+        // Errors can't be reported while dropping
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Writing datagrams to the socket bound to the path
+#[derive(Debug)]
+pub struct DatagramWriter {
+    /// The socket connected to the path
+    socket: UnixDatagram,
+}
+
+impl Write for DatagramWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.socket.send(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Finish for DatagramWriter {
+    fn finish(self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -1,8 +1,15 @@
-use std::{cell::RefCell, cmp::min, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    cmp::min,
+    io::{Seek, SeekFrom},
+    rc::Rc,
+};
 
 use anyhow::Result;
 
-use crate::RwBuilder;
+#[cfg(feature = "async-tokio")]
+use crate::AsyncRwBuilder;
+use crate::{Finish, RwBuilder, SeekableRwBuilder, seek_position};
 
 /// Type for building readers and writers on top of a `Vec` in memory.
 /// It is itself an `RwBuilder`, but can't be created through one.
@@ -12,6 +19,56 @@ pub struct Builder {
     /// The inner `Vec` which is used to write data to and read data from
     /// It is shared between the reader and the writer
     buffer: Rc<RefCell<Vec<u8>>>,
+    /// How often the buffer was cleared, so readers and writers notice it
+    clears: Rc<Cell<usize>>,
+}
+
+impl Builder {
+    /// Create a builder whose buffer starts out as a copy of `bytes`
+    #[must_use]
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        Self::from(bytes.to_vec())
+    }
+
+    /// Consume the builder and return the buffer, which is cloned if readers
+    /// or writers built from it are still alive
+    #[must_use]
+    pub fn into_inner(self) -> Vec<u8> {
+        Rc::try_unwrap(self.buffer)
+            .map_or_else(|buffer| buffer.borrow().clone(), RefCell::into_inner)
+    }
+
+    /// A copy of the bytes currently in the buffer
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<u8> {
+        self.buffer.borrow().clone()
+    }
+
+    /// Remove all bytes from the buffer.
+    /// Open readers start over at the beginning and open writers continue at
+    /// the end of the buffer, as if they were created after clearing it.
+    pub fn clear(&self) {
+        self.buffer.borrow_mut().clear();
+        self.clears.set(self.clears.get().wrapping_add(1));
+    }
+
+    /// The number of bytes currently in the buffer
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.buffer.borrow().len()
+    }
+
+    /// Whether the buffer currently holds no bytes
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.buffer.borrow().is_empty()
+    }
+}
+
+impl From<Vec<u8>> for Builder {
+    fn from(buffer: Vec<u8>) -> Self {
+        Self { buffer: Rc::new(RefCell::new(buffer)), clears: Rc::default() }
+    }
 }
 
 impl RwBuilder for Builder {
@@ -19,11 +76,40 @@ impl RwBuilder for Builder {
     type Writer = Writer;
 
     fn reader(&self) -> Result<Self::Reader> {
-        Ok(Reader { buffer: Rc::clone(&self.buffer), bytes_read: 0 })
+        Ok(Reader {
+            buffer: Rc::clone(&self.buffer),
+            bytes_read: 0,
+            clears: Rc::clone(&self.clears),
+            seen_clears: self.clears.get(),
+        })
     }
 
     fn writer(&self) -> Result<Self::Writer> {
-        Ok(Writer { buffer: Rc::clone(&self.buffer) })
+        let position = self.buffer.borrow().len();
+        Ok(Writer {
+            buffer: Rc::clone(&self.buffer),
+            position,
+            clears: Rc::clone(&self.clears),
+            seen_clears: self.clears.get(),
+        })
+    }
+}
+
+impl SeekableRwBuilder for Builder {}
+
+#[cfg(feature = "async-tokio")]
+// The buffer is shared through an `Rc`, so the futures are never `Send`
+#[allow(clippy::future_not_send)]
+impl AsyncRwBuilder for Builder {
+    type AsyncReader = Reader;
+    type AsyncWriter = Writer;
+
+    async fn async_reader(&self) -> Result<Self::AsyncReader> {
+        self.reader()
+    }
+
+    async fn async_writer(&self) -> Result<Self::AsyncWriter> {
+        self.writer()
     }
 }
 
@@ -34,24 +120,78 @@ pub struct Reader {
     buffer: Rc<RefCell<Vec<u8>>>,
     /// The read position in the buffer
     bytes_read: usize,
+    /// How often the buffer was cleared, shared with the builder
+    clears: Rc<Cell<usize>>,
+    /// How often the buffer was cleared when the position was last valid
+    seen_clears: usize,
+}
+
+impl Reader {
+    /// Start over at the beginning if the buffer was cleared since the last
+    /// read
+    fn restart_if_cleared(&mut self) {
+        if self.seen_clears != self.clears.get() {
+            self.seen_clears = self.clears.get();
+            self.bytes_read = 0;
+        }
+    }
+
+    /// Copy as many bytes as possible from the read position into `buf`
+    fn read_into(&mut self, buf: &mut [u8]) -> usize {
+        self.restart_if_cleared();
+        let borrowed = self.buffer.borrow();
+        let start = min(self.bytes_read, borrowed.len());
+        let byte_count = min(borrowed.len() - start, buf.len());
+        buf[..byte_count].copy_from_slice(&borrowed[start..start + byte_count]);
+        self.bytes_read = start + byte_count;
+        byte_count
+    }
 }
 
 impl std::io::Read for Reader {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let borrowed = self.buffer.borrow();
-        let byte_count = min(borrowed.len() - self.bytes_read, buf.len());
-        buf[..byte_count].copy_from_slice(&borrowed[self.bytes_read..self.bytes_read + byte_count]);
-        self.bytes_read += byte_count;
-        Ok(byte_count)
+        Ok(self.read_into(buf))
+    }
+}
+
+impl Seek for Reader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.restart_if_cleared();
+        let len = self.buffer.borrow().len();
+        self.bytes_read = seek_position(self.bytes_read, len, pos)?;
+        u64::try_from(self.bytes_read).map_err(std::io::Error::other)
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl tokio::io::AsyncRead for Reader {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let byte_count = self.get_mut().read_into(buf.initialize_unfilled());
+        buf.advance(byte_count);
+        std::task::Poll::Ready(Ok(()))
     }
 }
 
 impl std::io::Write for Writer {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.continue_if_cleared();
         let mut borrowed = self.buffer.borrow_mut();
-        let byte_count = buf.len();
-        borrowed.extend_from_slice(buf);
-        Ok(byte_count)
+        let end = self.position.checked_add(buf.len()).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "writing past the maximum size of a vec",
+            )
+        })?;
+        if borrowed.len() < end {
+            borrowed.resize(end, 0);
+        }
+        borrowed[self.position..end].copy_from_slice(buf);
+        self.position = end;
+        Ok(buf.len())
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
@@ -59,9 +199,66 @@ impl std::io::Write for Writer {
     }
 }
 
+impl Seek for Writer {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.continue_if_cleared();
+        let len = self.buffer.borrow().len();
+        self.position = seek_position(self.position, len, pos)?;
+        u64::try_from(self.position).map_err(std::io::Error::other)
+    }
+}
+
 /// Writing to a `Vec`
 #[derive(Debug)]
 pub struct Writer {
     /// The buffer shared between the builder, reader and writer
     buffer: Rc<RefCell<Vec<u8>>>,
+    /// The write position in the buffer, which starts at the end of the buffer
+    position: usize,
+    /// How often the buffer was cleared, shared with the builder
+    clears: Rc<Cell<usize>>,
+    /// How often the buffer was cleared when the position was last valid
+    seen_clears: usize,
+}
+
+impl Writer {
+    /// Continue at the end of the buffer if it was cleared since the last
+    /// write
+    fn continue_if_cleared(&mut self) {
+        if self.seen_clears != self.clears.get() {
+            self.seen_clears = self.clears.get();
+            self.position = self.buffer.borrow().len();
+        }
+    }
+}
+
+impl Finish for Writer {
+    fn finish(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl tokio::io::AsyncWrite for Writer {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        std::task::Poll::Ready(std::io::Write::write(self.get_mut(), buf))
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
 }
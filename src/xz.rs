@@ -0,0 +1,44 @@
+use anyhow::Result;
+
+use crate::{Codec, CoderBuilder, Constructor, Finish};
+
+/// The xz encoder and decoder builder, whose level is the preset between 0
+/// and 9 like the options of the `xz` command line tool
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Xz;
+
+impl Codec for Xz {
+    type Level = u32;
+}
+
+impl<R, W> CoderBuilder<R, W> for Xz
+where
+    R: std::io::Read,
+    W: std::io::Write,
+{
+    type Decoder = xz2::read::XzDecoder<R>;
+    type Encoder = xz2::write::XzEncoder<W>;
+
+    /// Fails with `ErrorKind::InvalidInput` if the preset is above 9
+    fn encoder(&self, writer: W, preset: u32) -> std::io::Result<Self::Encoder> {
+        let stream = xz2::stream::Stream::new_easy_encoder(preset, xz2::stream::Check::Crc64)?;
+        Ok(xz2::write::XzEncoder::new_stream(writer, stream))
+    }
+
+    /// The decoder reads all concatenated streams, like the `xz` command line
+    /// tool does
+    fn decoder(&self, reader: R) -> std::io::Result<Self::Decoder> {
+        Ok(xz2::read::XzDecoder::new_multi_decoder(reader))
+    }
+}
+
+impl<B> Constructor<B> for Xz {}
+
+impl<W> Finish for xz2::write::XzEncoder<W>
+where
+    W: std::io::Write + Finish,
+{
+    fn finish(self) -> Result<()> {
+        Self::finish(self)?.finish()
+    }
+}
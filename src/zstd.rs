@@ -0,0 +1,150 @@
+use std::{
+    fmt::{Debug, Formatter},
+    io::{BufReader, Write},
+};
+
+use anyhow::Result;
+
+use crate::{Codec, CoderBuilder, CompressionBuilder, Constructor, Finish};
+
+/// The zstd encoder and decoder builder, whose level is between 1 and 22 or
+/// negative for faster compression
+#[derive(Debug, Default, Clone)]
+pub struct Zstd {
+    /// The dictionary shared by the encoder and the decoder
+    dictionary: Option<Vec<u8>>,
+    /// Whether the encoder looks for matches far back in the stream
+    long_distance_matching: bool,
+    /// The number of worker threads used by the encoder, zero means the
+    /// calling thread compresses the data itself
+    workers: u32,
+}
+
+impl Zstd {
+    /// Compress and decompress using a dictionary, e.g. one trained with the
+    /// `zstd` command line tool
+    #[must_use]
+    pub fn dictionary(self, dictionary: Vec<u8>) -> Self {
+        Self { dictionary: Some(dictionary), ..self }
+    }
+
+    /// Enable long distance matching, which improves the compression of large
+    /// inputs with repetitions far apart
+    #[must_use]
+    pub fn long_distance_matching(self) -> Self {
+        Self { long_distance_matching: true, ..self }
+    }
+
+    /// Compress using the given number of background worker threads
+    #[must_use]
+    pub fn multithread(self, workers: u32) -> Self {
+        Self { workers, ..self }
+    }
+}
+
+impl Codec for Zstd {
+    type Level = i32;
+}
+
+impl<B> Constructor<B> for Zstd {}
+
+impl<R, W> CoderBuilder<R, W> for Zstd
+where
+    R: std::io::Read,
+    W: Write,
+{
+    type Decoder = zstd::stream::read::Decoder<'static, BufReader<R>>;
+    type Encoder = Encoder<W>;
+
+    fn encoder(&self, writer: W, level: i32) -> std::io::Result<Self::Encoder> {
+        let dictionary = self.dictionary.as_deref().unwrap_or_default();
+        let mut encoder = zstd::stream::write::Encoder::with_dictionary(writer, level, dictionary)?;
+        encoder.long_distance_matching(self.long_distance_matching)?;
+        if self.workers > 0 {
+            encoder.multithread(self.workers)?;
+        }
+        Ok(Encoder { encoder: Some(encoder) })
+    }
+
+    fn decoder(&self, reader: R) -> std::io::Result<Self::Decoder> {
+        let reader = BufReader::new(reader);
+        match &self.dictionary {
+            Some(dictionary) => zstd::stream::read::Decoder::with_dictionary(reader, dictionary),
+            None => zstd::stream::read::Decoder::with_buffer(reader),
+        }
+    }
+}
+
+impl<B> CompressionBuilder<B, Zstd> {
+    /// Compress and decompress using a dictionary, see `Zstd::dictionary`
+    #[must_use]
+    pub fn dictionary(self, dictionary: Vec<u8>) -> Self {
+        self.map_coder(|zstd| zstd.dictionary(dictionary))
+    }
+
+    /// Enable long distance matching, see `Zstd::long_distance_matching`
+    #[must_use]
+    pub fn long_distance_matching(self) -> Self {
+        self.map_coder(Zstd::long_distance_matching)
+    }
+
+    /// Compress using background worker threads, see `Zstd::multithread`
+    #[must_use]
+    pub fn multithread(self, workers: u32) -> Self {
+        self.map_coder(|zstd| zstd.multithread(workers))
+    }
+}
+
+/// Writer that compresses using zstd and, like the flate2 encoders, finishes
+/// the stream when dropped
+pub struct Encoder<W>
+where
+    W: Write,
+{
+    /// The zstd encoder, which is taken when the writer is finished
+    encoder: Option<zstd::stream::write::Encoder<'static, W>>,
+}
+
+impl<W> Debug for Encoder<W>
+where
+    W: Write + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let writer = self.encoder.as_ref().map(zstd::stream::write::Encoder::get_ref);
+        f.debug_struct("Encoder").field("writer", &writer).finish_non_exhaustive()
+    }
+}
+
+impl<W> Write for Encoder<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.encoder.as_mut().map_or(Ok(0), |encoder| encoder.write(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.encoder.as_mut().map_or(Ok(()), Write::flush)
+    }
+}
+
+impl<W> Finish for Encoder<W>
+where
+    W: Write + Finish,
+{
+    fn finish(mut self) -> Result<()> {
+        self.encoder.take().map_or(Ok(()), |encoder| encoder.finish()?.finish())
+    }
+}
+
+impl<W> Drop for Encoder<W>
+where
+    W: Write,
+{
+    fn drop(&mut self) {
+        if let Some(encoder) = &mut self.encoder {
+            // Errors can't be reported while dropping, use `finish` instead
+            let _ = encoder.do_finish();
+        }
+    }
+}